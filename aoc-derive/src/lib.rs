@@ -0,0 +1,356 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Ident, Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+	parse_macro_input, Data, DeriveInput, Field, Fields, GenericArgument,
+	LitStr, PathArguments, Type,
+};
+
+/// Derives `FromStr` for a struct with named fields from a line format:
+///
+/// ```ignore
+/// #[derive(AocParse)]
+/// #[aoc(format = "{node} = ({left}, {right})")]
+/// struct Element {
+///     node: String,
+///     left: String,
+///     right: String,
+/// }
+/// ```
+///
+/// Every field appears once as `{name}` and is parsed with its own
+/// `FromStr`, while `{_}` skips text that isn't needed. A `Vec<T>` field
+/// marked `#[aoc(separator = ", ")]` is split on the separator and each
+/// item parsed as a `T`. Literal text must match exactly, `{{` and `}}`
+/// stand for braces, and two fields can't be adjacent. Errors are
+/// `aoc::parse::ParseError`s pointing at the column where the line stopped
+/// matching.
+///
+/// A struct with a lifetime gets `TryFrom<&'a str>` instead of `FromStr`,
+/// so its `&'a str` fields can borrow from the line.
+#[proc_macro_derive(AocParse, attributes(aoc))]
+pub fn derive_aoc_parse(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	expand(&input)
+		.unwrap_or_else(syn::Error::into_compile_error)
+		.into()
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Segment {
+	Literal(String),
+	Field(String),
+}
+
+/// Splits a format string into literal text and `{field}` placeholders.
+fn segments(format: &str) -> Result<Vec<Segment>, String> {
+	let mut segments = Vec::new();
+	let mut literal = String::new();
+	let mut chars = format.chars().peekable();
+
+	while let Some(c) = chars.next() {
+		match c {
+			'{' if chars.peek() == Some(&'{') => {
+				chars.next();
+				literal.push('{');
+			}
+			'}' if chars.peek() == Some(&'}') => {
+				chars.next();
+				literal.push('}');
+			}
+			'{' => {
+				let mut name = String::new();
+				loop {
+					match chars.next() {
+						Some('}') => break,
+						Some(c) => name.push(c),
+						None => return Err("Unclosed `{` in format".into()),
+					}
+				}
+				let name = name.trim();
+
+				if name.is_empty() {
+					return Err("Empty `{}` placeholder in format".into());
+				}
+				if !literal.is_empty() {
+					segments
+						.push(Segment::Literal(std::mem::take(&mut literal)));
+				}
+				if let Some(Segment::Field(previous)) = segments.last() {
+					return Err(format!(
+						"Fields `{previous}` and `{name}` need literal text \
+						 between them"
+					));
+				}
+
+				segments.push(Segment::Field(name.to_string()));
+			}
+			'}' => return Err("Unmatched `}` in format".into()),
+			_ => literal.push(c),
+		}
+	}
+
+	if !literal.is_empty() {
+		segments.push(Segment::Literal(literal));
+	}
+
+	Ok(segments)
+}
+
+/// The value of `#[aoc(key = "...")]` on the given attributes, if any.
+fn attribute(
+	attrs: &[syn::Attribute],
+	key: &str,
+) -> syn::Result<Option<LitStr>> {
+	let mut value = None;
+
+	for attr in attrs.iter().filter(|attr| attr.path().is_ident("aoc")) {
+		attr.parse_nested_meta(|meta| {
+			if meta.path.is_ident(key) {
+				value = Some(meta.value()?.parse()?);
+				Ok(())
+			} else {
+				Err(meta.error(format!("Expected `{key} = \"...\"`")))
+			}
+		})?;
+	}
+
+	Ok(value)
+}
+
+/// Whether the type is `&str`, with any lifetime.
+fn is_str(ty: &Type) -> bool {
+	matches!(ty, Type::Reference(reference)
+		if reference.mutability.is_none()
+			&& matches!(&*reference.elem, Type::Path(path) if path.path.is_ident("str")))
+}
+
+/// `T` for a field of type `Vec<T>`.
+fn vec_item(ty: &Type) -> Option<&Type> {
+	let Type::Path(path) = ty else {
+		return None;
+	};
+	let last = path.path.segments.last()?;
+	if last.ident != "Vec" {
+		return None;
+	}
+	let PathArguments::AngleBracketed(args) = &last.arguments else {
+		return None;
+	};
+
+	match args.args.first()? {
+		GenericArgument::Type(item) => Some(item),
+		_ => None,
+	}
+}
+
+/// Names for the generated code's own variables. They are hygienic, so
+/// fields called `line`, `rest` or `text` don't clash with them.
+struct Locals {
+	line: Ident,
+	rest: Ident,
+	text: Ident,
+}
+
+impl Locals {
+	fn new() -> Self {
+		let local = |name| Ident::new(name, Span::mixed_site());
+		Self {
+			line: local("line"),
+			rest: local("rest"),
+			text: local("text"),
+		}
+	}
+}
+
+fn parse_field(
+	field: &Field,
+	text: &Ident,
+	line: &Ident,
+) -> syn::Result<TokenStream2> {
+	let ident = field.ident.as_ref().expect("Named field");
+	let name = ident.to_string();
+	let ty = &field.ty;
+
+	match attribute(&field.attrs, "separator")? {
+		Some(separator) => {
+			let item = vec_item(ty).ok_or_else(|| {
+				syn::Error::new_spanned(ty, "A `separator` field must be a Vec")
+			})?;
+			if is_str(item) {
+				return Ok(quote! {
+					let #ident: #ty = #text.split(#separator).collect();
+				});
+			}
+			Ok(quote! {
+				let #ident = ::aoc::parse::derive::list::<#item>(
+					#line, #text, #separator, #name,
+				)?;
+			})
+		}
+		None if is_str(ty) => Ok(quote! {
+			let #ident: #ty = #text;
+		}),
+		None => Ok(quote! {
+			let #ident = ::aoc::parse::derive::field::<#ty>(#line, #text, #name)?;
+		}),
+	}
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+	let name = &input.ident;
+
+	let Data::Struct(data) = &input.data else {
+		return Err(syn::Error::new_spanned(
+			name,
+			"AocParse can only be derived for structs",
+		));
+	};
+	let Fields::Named(fields) = &data.fields else {
+		return Err(syn::Error::new_spanned(
+			name,
+			"AocParse needs a struct with named fields",
+		));
+	};
+
+	let format = attribute(&input.attrs, "format")?.ok_or_else(|| {
+		syn::Error::new_spanned(name, "Missing `#[aoc(format = \"...\")]`")
+	})?;
+	let segments = segments(&format.value())
+		.map_err(|message| syn::Error::new_spanned(&format, message))?;
+
+	for field in &fields.named {
+		let ident = field.ident.as_ref().expect("Named field");
+		let uses = segments
+			.iter()
+			.filter(|s| matches!(s, Segment::Field(f) if ident == f))
+			.count();
+		if uses != 1 {
+			return Err(syn::Error::new_spanned(
+				ident,
+				format!("Field must appear exactly once in the format, not {uses} times"),
+			));
+		}
+	}
+
+	let Locals { line, rest, text } = Locals::new();
+	let mut steps = Vec::new();
+
+	for (index, segment) in segments.iter().enumerate() {
+		match segment {
+			Segment::Literal(literal) => steps.push(quote! {
+				let #rest = ::aoc::parse::derive::literal(#line, #rest, #literal)?;
+			}),
+			Segment::Field(field_name) => {
+				let text = match field_name.as_str() {
+					"_" => format_ident!("_"),
+					_ => text.clone(),
+				};
+				steps.push(match segments.get(index + 1) {
+					Some(Segment::Literal(next)) => quote! {
+						let (#text, #rest) =
+							::aoc::parse::derive::until(#line, #rest, #next)?;
+					},
+					_ => quote! {
+						let (#text, #rest) = (#rest, &#rest[#rest.len()..]);
+					},
+				});
+
+				if field_name == "_" {
+					continue;
+				}
+
+				let field = fields
+					.named
+					.iter()
+					.find(|field| {
+						field.ident.as_ref().is_some_and(|i| i == field_name)
+					})
+					.ok_or_else(|| {
+						syn::Error::new_spanned(
+							&format,
+							format!("No field named `{field_name}`"),
+						)
+					})?;
+
+				steps.push(parse_field(field, &text, &line)?);
+			}
+		}
+	}
+
+	let idents = fields.named.iter().map(|field| &field.ident);
+	let (impl_generics, ty_generics, where_clause) =
+		input.generics.split_for_impl();
+
+	let body = quote! {
+		let #rest = #line;
+		#(#steps)*
+		::aoc::parse::derive::end(#line, #rest)?;
+
+		Ok(Self { #(#idents),* })
+	};
+
+	match input.generics.lifetimes().next() {
+		Some(lifetime) => {
+			let lifetime = &lifetime.lifetime;
+			Ok(quote! {
+				impl #impl_generics ::std::convert::TryFrom<&#lifetime str>
+					for #name #ty_generics #where_clause
+				{
+					type Error = ::aoc::parse::ParseError;
+
+					fn try_from(
+						#line: &#lifetime str,
+					) -> ::std::result::Result<Self, Self::Error> {
+						#body
+					}
+				}
+			})
+		}
+		None => Ok(quote! {
+			impl #impl_generics ::std::str::FromStr for #name #ty_generics #where_clause {
+				type Err = ::aoc::parse::ParseError;
+
+				fn from_str(#line: &str) -> ::std::result::Result<Self, Self::Err> {
+					#body
+				}
+			}
+		}),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn splits_formats() {
+		assert_eq!(
+			segments("{node} = ({left}, {right})"),
+			Ok(vec![
+				Segment::Field("node".into()),
+				Segment::Literal(" = (".into()),
+				Segment::Field("left".into()),
+				Segment::Literal(", ".into()),
+				Segment::Field("right".into()),
+				Segment::Literal(")".into()),
+			])
+		);
+		assert_eq!(
+			segments("{{{ x }}}"),
+			Ok(vec![
+				Segment::Literal("{".into()),
+				Segment::Field("x".into()),
+				Segment::Literal("}".into()),
+			])
+		);
+	}
+
+	#[test]
+	fn rejects_bad_formats() {
+		assert!(segments("{a}{b}").is_err());
+		assert!(segments("a {} b").is_err());
+		assert!(segments("a } b").is_err());
+		assert!(segments("a {b").is_err());
+	}
+}
@@ -0,0 +1,64 @@
+use std::{
+	env,
+	fmt::Write,
+	fs,
+	path::{Path, PathBuf},
+};
+
+/// Whether `name` looks like `day_NN.txt`, or `day_NN.txt.enc` when
+/// encrypted.
+fn is_day(name: &str) -> bool {
+	let name = name.strip_suffix(".enc").unwrap_or(name);
+	name.strip_prefix("day_")
+		.and_then(|name| name.strip_suffix(".txt"))
+		.is_some_and(|day| {
+			day.len() == 2 && day.bytes().all(|b| b.is_ascii_digit())
+		})
+}
+
+/// Adds the inputs in `directory` to `days`, keyed by their path from the
+/// crate root, and those of every profile in its subdirectories.
+fn collect(directory: &Path, key: &str, days: &mut Vec<(String, PathBuf)>) {
+	println!("cargo:rerun-if-changed={}", directory.display());
+	let Ok(entries) = fs::read_dir(directory) else {
+		return;
+	};
+
+	for entry in entries.flatten() {
+		let name = entry.file_name().to_string_lossy().into_owned();
+		let path = entry.path();
+		if path.is_dir() && key == "inputs" {
+			collect(&path, &format!("{key}/{name}"), days);
+		} else if is_day(&name) && path.is_file() {
+			days.push((format!("{key}/{name}"), path));
+		}
+	}
+}
+
+/// Writes the table of embedded inputs for `aoc::inputs`. It is empty
+/// unless the `embed-inputs` feature is on, and only holds the inputs that
+/// exist, so a checkout without them still builds.
+fn main() {
+	println!("cargo:rerun-if-changed=build.rs");
+
+	let mut days = Vec::new();
+	if env::var_os("CARGO_FEATURE_EMBED_INPUTS").is_some() {
+		let root = env::var("CARGO_MANIFEST_DIR").unwrap();
+		collect(&Path::new(&root).join("inputs"), "inputs", &mut days);
+		days.sort();
+	}
+
+	let mut table =
+		String::from("pub static EMBEDDED: &[(&str, &[u8])] = &[\n");
+	for (key, path) in &days {
+		let _ = writeln!(
+			table,
+			"\t({key:?}, include_bytes!({:?})),",
+			path.display().to_string()
+		);
+	}
+	table.push_str("];\n");
+
+	let out = env::var("OUT_DIR").unwrap();
+	fs::write(Path::new(&out).join("inputs.rs"), table).unwrap();
+}
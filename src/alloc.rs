@@ -0,0 +1,116 @@
+use std::{
+	alloc::{GlobalAlloc, Layout, System},
+	cell::Cell,
+	fmt::{self, Display},
+};
+
+thread_local! {
+	static COUNT: Cell<usize> = const { Cell::new(0) };
+	static BYTES: Cell<usize> = const { Cell::new(0) };
+}
+
+/// The system allocator, counting the allocations made on each thread. A
+/// binary opts in with
+///
+/// ```ignore
+/// #[global_allocator]
+/// static ALLOCATOR: aoc::alloc::Counting = aoc::alloc::Counting;
+/// ```
+///
+/// Counts are per thread so tests running side by side don't see each
+/// other's allocations.
+pub struct Counting;
+
+fn record(size: usize) {
+	// Fails only while the thread is being torn down; those allocations go
+	// uncounted.
+	let _ = COUNT.try_with(|count| count.set(count.get() + 1));
+	let _ = BYTES.try_with(|bytes| bytes.set(bytes.get() + size));
+}
+
+unsafe impl GlobalAlloc for Counting {
+	unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+		record(layout.size());
+		unsafe { System.alloc(layout) }
+	}
+
+	unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+		record(layout.size());
+		unsafe { System.alloc_zeroed(layout) }
+	}
+
+	unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+		unsafe { System.dealloc(ptr, layout) }
+	}
+
+	/// A reallocation counts as a new allocation of the new size.
+	unsafe fn realloc(
+		&self,
+		ptr: *mut u8,
+		layout: Layout,
+		new_size: usize,
+	) -> *mut u8 {
+		record(new_size);
+		unsafe { System.realloc(ptr, layout, new_size) }
+	}
+}
+
+/// Allocations made, and the bytes they asked for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Allocations {
+	pub count: usize,
+	pub bytes: usize,
+}
+
+impl Display for Allocations {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{} allocations, {} bytes", self.count, self.bytes)
+	}
+}
+
+/// Allocations made on this thread so far. Always zero unless the binary
+/// uses [`Counting`] as its global allocator.
+pub fn allocations() -> Allocations {
+	Allocations {
+		count: COUNT.with(Cell::get),
+		bytes: BYTES.with(Cell::get),
+	}
+}
+
+/// Runs `f`, returning its result and the allocations it made.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Allocations) {
+	let before = allocations();
+	let value = f();
+	let after = allocations();
+
+	(
+		value,
+		Allocations {
+			count: after.count - before.count,
+			bytes: after.bytes - before.bytes,
+		},
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[global_allocator]
+	static ALLOCATOR: Counting = Counting;
+
+	#[test]
+	fn counts_allocations() {
+		let (_, none) = measure(|| 1 + 1);
+		assert_eq!(none, Allocations::default());
+
+		let (vector, some) = measure(|| {
+			let mut vector = Vec::with_capacity(4);
+			vector.extend([1u32, 2, 3, 4, 5]);
+			vector
+		});
+		assert_eq!(vector.len(), 5);
+		assert_eq!(some.count, 2);
+		assert!(some.bytes >= 4 * 4 + 5 * 4);
+	}
+}
@@ -0,0 +1,315 @@
+use crate::{grid::Grid, render::Rgb};
+use anyhow::Result;
+use std::{
+	fmt::Write as _,
+	io::{self, BufRead, Write},
+	sync::mpsc::{self, RecvTimeoutError},
+	thread,
+	time::{Duration, Instant},
+};
+
+/// A snapshot of a simulation: a grid of characters and a status line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+	pub grid: Grid<char>,
+	pub status: String,
+}
+
+impl Frame {
+	pub fn new(grid: Grid<char>, status: impl Into<String>) -> Self {
+		Self {
+			grid,
+			status: status.into(),
+		}
+	}
+
+	/// Frame from lines of text, padded with spaces to the longest line.
+	pub fn text<S: AsRef<str>>(
+		lines: impl IntoIterator<Item = S>,
+		status: impl Into<String>,
+	) -> Self {
+		let lines: Vec<Vec<char>> = lines
+			.into_iter()
+			.map(|line| line.as_ref().chars().collect())
+			.collect();
+		let width = lines.iter().map(Vec::len).max().unwrap_or(0);
+
+		let cells = lines
+			.iter()
+			.flat_map(|line| {
+				line.iter()
+					.copied()
+					.chain(std::iter::repeat(' '))
+					.take(width)
+			})
+			.collect();
+
+		Self::new(Grid::from_cells(width, lines.len(), cells), status)
+	}
+
+	/// The frame as text, with ANSI true color escapes for every cell the
+	/// palette gives a color to.
+	pub fn render(&self, palette: &dyn Fn(char) -> Option<Rgb>) -> String {
+		let mut text = String::new();
+
+		for row in self.grid.rows() {
+			let mut current = None;
+
+			for &c in row {
+				let color = palette(c);
+				if color != current {
+					match color {
+						Some(Rgb(r, g, b)) => {
+							let _ = write!(text, "\x1b[38;2;{r};{g};{b}m");
+						}
+						None => text.push_str("\x1b[0m"),
+					}
+					current = color;
+				}
+				text.push(c);
+			}
+
+			if current.is_some() {
+				text.push_str("\x1b[0m");
+			}
+			text.push('\n');
+		}
+
+		text.push_str(&self.status);
+		text.push('\n');
+		text
+	}
+}
+
+/// Controls read from the player's input, one per line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+	/// `p` or an empty line.
+	TogglePause,
+	/// `n`: pause and show the next frame.
+	Step,
+	/// `b`: pause and show the previous frame.
+	Back,
+	/// `g <frame>`: pause and show the given frame.
+	Jump(usize),
+	/// `+` or `-`: double or halve the frame rate.
+	Faster,
+	Slower,
+	/// `q`
+	Quit,
+}
+
+impl Command {
+	pub fn parse(line: &str) -> Option<Self> {
+		let mut parts = line.split_whitespace();
+
+		match parts.next() {
+			None | Some("p") => Some(Command::TogglePause),
+			Some("n") => Some(Command::Step),
+			Some("b") => Some(Command::Back),
+			Some("g") => parts.next()?.parse().ok().map(Command::Jump),
+			Some("+") => Some(Command::Faster),
+			Some("-") => Some(Command::Slower),
+			Some("q") => Some(Command::Quit),
+			_ => None,
+		}
+	}
+}
+
+/// Plays frames in the terminal. Frames are pulled from the iterator as
+/// they are needed and kept, so the player can step back and jump to any
+/// frame it has already produced or can still produce.
+pub struct Player<I> {
+	frames: I,
+	seen: Vec<Frame>,
+	index: usize,
+	paused: bool,
+	fps: f64,
+	palette: Box<dyn Fn(char) -> Option<Rgb>>,
+}
+
+impl<I: Iterator<Item = Frame>> Player<I> {
+	pub fn new(frames: impl IntoIterator<IntoIter = I>) -> Self {
+		Self {
+			frames: frames.into_iter(),
+			seen: Vec::new(),
+			index: 0,
+			paused: false,
+			fps: 10.0,
+			palette: Box::new(|_| None),
+		}
+	}
+
+	pub fn fps(mut self, fps: f64) -> Self {
+		self.fps = fps;
+		self
+	}
+
+	pub fn palette(
+		mut self,
+		palette: impl Fn(char) -> Option<Rgb> + 'static,
+	) -> Self {
+		self.palette = Box::new(palette);
+		self
+	}
+
+	pub fn paused(mut self, paused: bool) -> Self {
+		self.paused = paused;
+		self
+	}
+
+	/// Index of the frame being shown.
+	pub fn index(&self) -> usize {
+		self.index
+	}
+
+	/// The frame at `index`, pulling frames from the iterator up to it.
+	pub fn frame(&mut self, index: usize) -> Option<&Frame> {
+		while self.seen.len() <= index {
+			self.seen.push(self.frames.next()?);
+		}
+
+		self.seen.get(index)
+	}
+
+	/// Applies a command, returning `false` when playback should stop.
+	pub fn apply(&mut self, command: Command) -> bool {
+		match command {
+			Command::TogglePause => self.paused = !self.paused,
+			Command::Step => {
+				self.paused = true;
+				self.advance();
+			}
+			Command::Back => {
+				self.paused = true;
+				self.index = self.index.saturating_sub(1);
+			}
+			Command::Jump(index) => {
+				self.paused = true;
+				self.index = match self.frame(index) {
+					Some(_) => index,
+					None => self.seen.len().saturating_sub(1),
+				};
+			}
+			Command::Faster => self.fps *= 2.0,
+			Command::Slower => self.fps /= 2.0,
+			Command::Quit => return false,
+		}
+
+		true
+	}
+
+	/// Moves to the next frame, returning `false` if there is none.
+	pub fn advance(&mut self) -> bool {
+		let next = self.frame(self.index + 1).is_some();
+		if next {
+			self.index += 1;
+		}
+		next
+	}
+
+	/// Plays until the last frame is shown or `q` is entered. Commands are
+	/// read a line at a time from stdin.
+	pub fn play(mut self) -> Result<()> {
+		let (sender, receiver) = mpsc::channel();
+
+		thread::spawn(move || {
+			for line in io::stdin().lock().lines().map_while(|l| l.ok()) {
+				if let Some(command) = Command::parse(&line) {
+					if sender.send(command).is_err() {
+						break;
+					}
+				}
+			}
+		});
+
+		let mut stdout = io::stdout().lock();
+		let mut next_tick = Instant::now();
+
+		loop {
+			if self.frame(self.index).is_some() {
+				let text = self.seen[self.index].render(&*self.palette);
+				writeln!(
+					stdout,
+					"\x1b[H\x1b[2J{}frame {}{}  [p]ause [n]ext [b]ack [g N] [+/-] [q]uit",
+					text,
+					self.index,
+					if self.paused { " (paused)" } else { "" }
+				)?;
+				stdout.flush()?;
+			}
+
+			next_tick += Duration::from_secs_f64(1.0 / self.fps.max(0.001));
+			let timeout = next_tick.saturating_duration_since(Instant::now());
+
+			match receiver.recv_timeout(timeout) {
+				Ok(command) => {
+					if !self.apply(command) {
+						break;
+					}
+					next_tick = Instant::now();
+				}
+				Err(RecvTimeoutError::Timeout) if !self.paused => {
+					if !self.advance() {
+						break;
+					}
+				}
+				Err(RecvTimeoutError::Timeout) => {}
+				Err(RecvTimeoutError::Disconnected) if self.paused => break,
+				Err(RecvTimeoutError::Disconnected) => {
+					thread::sleep(timeout);
+					if !self.advance() {
+						break;
+					}
+				}
+			}
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn frames() -> impl Iterator<Item = Frame> {
+		(0..5).map(|i| Frame::text([format!("{i}")], format!("step {i}")))
+	}
+
+	#[test]
+	fn commands() {
+		assert_eq!(Command::parse(""), Some(Command::TogglePause));
+		assert_eq!(Command::parse("g 12"), Some(Command::Jump(12)));
+		assert_eq!(Command::parse("g x"), None);
+		assert_eq!(Command::parse("q"), Some(Command::Quit));
+	}
+
+	#[test]
+	fn stepping_and_jumping() {
+		let mut player = Player::new(frames());
+
+		assert!(player.apply(Command::Step));
+		assert_eq!(player.index(), 1);
+		player.apply(Command::Jump(3));
+		assert_eq!(player.index(), 3);
+		player.apply(Command::Back);
+		assert_eq!(player.index(), 2);
+		player.apply(Command::Jump(100));
+		assert_eq!(player.index(), 4);
+		assert!(!player.advance());
+		assert!(!player.apply(Command::Quit));
+	}
+
+	#[test]
+	fn ansi_colors() {
+		let frame = Frame::text(["#.#", "##"], "done");
+		let palette = |c| (c == '#').then_some(Rgb::RED);
+
+		assert_eq!(
+			frame.render(&palette),
+			"\x1b[38;2;220;50;47m#\x1b[0m.\x1b[38;2;220;50;47m#\x1b[0m\n\
+			 \x1b[38;2;220;50;47m##\x1b[0m \ndone\n"
+		);
+	}
+}
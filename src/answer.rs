@@ -0,0 +1,182 @@
+#[cfg(feature = "bigint")]
+use crate::bigint::BigUint;
+use crate::math;
+#[cfg(not(feature = "bigint"))]
+use crate::math::MathError;
+use std::fmt::{self, Display};
+
+/// A non-negative puzzle answer. Arithmetic is checked: on overflow it
+/// moves to a `BigUint` with the `bigint` feature, and fails with
+/// `MathError::Overflow` without it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+	Small(u128),
+	/// Only ever holds values too large for `Small`.
+	#[cfg(feature = "bigint")]
+	Big(BigUint),
+}
+
+impl Answer {
+	/// The value, if it fits in a `u128`.
+	pub fn to_u128(&self) -> Option<u128> {
+		match self {
+			Answer::Small(value) => Some(*value),
+			#[cfg(feature = "bigint")]
+			Answer::Big(_) => None,
+		}
+	}
+
+	#[cfg(feature = "bigint")]
+	fn to_big(&self) -> BigUint {
+		match self {
+			Answer::Small(value) => BigUint::from(*value),
+			Answer::Big(value) => value.clone(),
+		}
+	}
+
+	fn combine(&self, other: &Answer, op: Op) -> math::Result<Answer> {
+		if let (Some(a), Some(b)) = (self.to_u128(), other.to_u128()) {
+			if let Some(value) = op.small(a, b) {
+				return Ok(Answer::Small(value));
+			}
+		}
+
+		#[cfg(feature = "bigint")]
+		return Ok(Answer::from(op.big(&self.to_big(), &other.to_big())));
+
+		#[cfg(not(feature = "bigint"))]
+		Err(MathError::Overflow)
+	}
+
+	pub fn checked_add(&self, other: &Answer) -> math::Result<Answer> {
+		self.combine(other, Op::Add)
+	}
+
+	pub fn checked_mul(&self, other: &Answer) -> math::Result<Answer> {
+		self.combine(other, Op::Mul)
+	}
+
+	pub fn checked_lcm(&self, other: &Answer) -> math::Result<Answer> {
+		self.combine(other, Op::Lcm)
+	}
+
+	pub fn sum<'a>(
+		answers: impl IntoIterator<Item = &'a Answer>,
+	) -> math::Result<Answer> {
+		answers
+			.into_iter()
+			.try_fold(Answer::Small(0), |acc, x| acc.checked_add(x))
+	}
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Op {
+	Add,
+	Mul,
+	Lcm,
+}
+
+impl Op {
+	fn small(self, a: u128, b: u128) -> Option<u128> {
+		match self {
+			Op::Add => a.checked_add(b),
+			Op::Mul => a.checked_mul(b),
+			Op::Lcm => math::lcm(a, b).ok(),
+		}
+	}
+
+	#[cfg(feature = "bigint")]
+	fn big(self, a: &BigUint, b: &BigUint) -> BigUint {
+		match self {
+			Op::Add => a + b,
+			Op::Mul => a * b,
+			Op::Lcm if a.is_zero() || b.is_zero() => BigUint::zero(),
+			Op::Lcm => {
+				let (quotient, _) = a.div_rem(&a.gcd(b)).expect("Non-zero gcd");
+				&quotient * b
+			}
+		}
+	}
+}
+
+impl From<u128> for Answer {
+	fn from(value: u128) -> Self {
+		Answer::Small(value)
+	}
+}
+
+impl From<u64> for Answer {
+	fn from(value: u64) -> Self {
+		Answer::Small(value as u128)
+	}
+}
+
+impl From<usize> for Answer {
+	fn from(value: usize) -> Self {
+		Answer::Small(value as u128)
+	}
+}
+
+#[cfg(feature = "bigint")]
+impl From<BigUint> for Answer {
+	fn from(value: BigUint) -> Self {
+		match value.to_u128() {
+			Some(value) => Answer::Small(value),
+			None => Answer::Big(value),
+		}
+	}
+}
+
+impl Display for Answer {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Answer::Small(value) => write!(f, "{value}"),
+			#[cfg(feature = "bigint")]
+			Answer::Big(value) => write!(f, "{value}"),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn small_arithmetic() {
+		let (a, b) = (Answer::from(12u64), Answer::from(18u64));
+		assert_eq!(a.checked_add(&b), Ok(Answer::Small(30)));
+		assert_eq!(a.checked_mul(&b), Ok(Answer::Small(216)));
+		assert_eq!(a.checked_lcm(&b), Ok(Answer::Small(36)));
+		assert_eq!(Answer::sum(&[a, b]), Ok(Answer::Small(30)));
+	}
+
+	#[test]
+	fn overflow() {
+		let max = Answer::from(u128::MAX);
+		let sum = max.checked_add(&Answer::from(1u64));
+
+		#[cfg(not(feature = "bigint"))]
+		assert_eq!(sum, Err(MathError::Overflow));
+
+		#[cfg(feature = "bigint")]
+		{
+			let sum = sum.unwrap();
+			assert_eq!(
+				sum.to_string(),
+				"340282366920938463463374607431768211456"
+			);
+			assert_eq!(sum.to_u128(), None);
+
+			let primes = [u64::MAX as u128 - 58, u64::MAX as u128 - 82];
+			let lcm = Answer::from(primes[0] * primes[1])
+				.checked_lcm(&Answer::from(primes[0] * 3))
+				.unwrap();
+			assert_eq!(
+				lcm,
+				Answer::from(
+					&BigUint::from(primes[0] * primes[1]) * &BigUint::from(3)
+				)
+			);
+		}
+	}
+}
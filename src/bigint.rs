@@ -0,0 +1,279 @@
+use std::{
+	cmp::Ordering,
+	fmt::{self, Display},
+	ops::{Add, Mul},
+};
+
+/// An arbitrary-precision unsigned integer, stored as little-endian 32-bit
+/// limbs without trailing zero limbs, so zero has no limbs at all.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+	limbs: Vec<u32>,
+}
+
+impl BigUint {
+	pub fn zero() -> Self {
+		Self::default()
+	}
+
+	fn normalized(mut limbs: Vec<u32>) -> Self {
+		while limbs.last() == Some(&0) {
+			limbs.pop();
+		}
+		Self { limbs }
+	}
+
+	pub fn is_zero(&self) -> bool {
+		self.limbs.is_empty()
+	}
+
+	/// The value, if it fits in a `u128`.
+	pub fn to_u128(&self) -> Option<u128> {
+		if self.limbs.len() > 4 {
+			return None;
+		}
+
+		Some(
+			self.limbs
+				.iter()
+				.rev()
+				.fold(0, |acc, &limb| acc << 32 | limb as u128),
+		)
+	}
+
+	/// Number of bits needed to write the value, 0 for zero.
+	pub fn bits(&self) -> usize {
+		match self.limbs.last() {
+			Some(&top) => self.limbs.len() * 32 - top.leading_zeros() as usize,
+			None => 0,
+		}
+	}
+
+	fn bit(&self, index: usize) -> bool {
+		self.limbs
+			.get(index / 32)
+			.is_some_and(|limb| limb >> (index % 32) & 1 == 1)
+	}
+
+	fn set_bit(&mut self, index: usize) {
+		if self.limbs.len() <= index / 32 {
+			self.limbs.resize(index / 32 + 1, 0);
+		}
+		self.limbs[index / 32] |= 1 << (index % 32);
+	}
+
+	/// `self = self * 2 + bit`.
+	fn push_bit(&mut self, bit: bool) {
+		let mut carry = bit as u32;
+		for limb in &mut self.limbs {
+			let next = *limb >> 31;
+			*limb = *limb << 1 | carry;
+			carry = next;
+		}
+		if carry != 0 {
+			self.limbs.push(carry);
+		}
+	}
+
+	/// `self -= other`, which must not be larger.
+	fn sub_assign(&mut self, other: &BigUint) {
+		let mut borrow = 0i64;
+		for (index, limb) in self.limbs.iter_mut().enumerate() {
+			let rhs = other.limbs.get(index).copied().unwrap_or(0) as i64;
+			let difference = *limb as i64 - rhs - borrow;
+			borrow = (difference < 0) as i64;
+			*limb = difference.rem_euclid(1 << 32) as u32;
+		}
+		debug_assert_eq!(borrow, 0, "Subtraction underflowed");
+		*self = Self::normalized(std::mem::take(&mut self.limbs));
+	}
+
+	/// Quotient and remainder, or `None` when dividing by zero.
+	pub fn div_rem(&self, divisor: &BigUint) -> Option<(BigUint, BigUint)> {
+		if divisor.is_zero() {
+			return None;
+		}
+
+		if let [small] = divisor.limbs[..] {
+			let (quotient, remainder) = self.div_rem_small(small);
+			return Some((quotient, BigUint::from(remainder as u128)));
+		}
+
+		// Binary long division, one bit of the dividend at a time.
+		let mut quotient = BigUint::zero();
+		let mut remainder = BigUint::zero();
+
+		for index in (0..self.bits()).rev() {
+			remainder.push_bit(self.bit(index));
+			if remainder >= *divisor {
+				remainder.sub_assign(divisor);
+				quotient.set_bit(index);
+			}
+		}
+
+		Some((quotient, remainder))
+	}
+
+	fn div_rem_small(&self, divisor: u32) -> (BigUint, u32) {
+		let mut limbs = vec![0; self.limbs.len()];
+		let mut remainder = 0u64;
+
+		for (index, &limb) in self.limbs.iter().enumerate().rev() {
+			let current = remainder << 32 | limb as u64;
+			limbs[index] = (current / divisor as u64) as u32;
+			remainder = current % divisor as u64;
+		}
+
+		(Self::normalized(limbs), remainder as u32)
+	}
+
+	pub fn gcd(&self, other: &BigUint) -> BigUint {
+		let (mut a, mut b) = (self.clone(), other.clone());
+		while let Some((_, remainder)) = a.div_rem(&b) {
+			(a, b) = (b, remainder);
+		}
+		a
+	}
+}
+
+impl From<u128> for BigUint {
+	fn from(mut value: u128) -> Self {
+		let mut limbs = Vec::new();
+		while value != 0 {
+			limbs.push(value as u32);
+			value >>= 32;
+		}
+		Self { limbs }
+	}
+}
+
+impl Ord for BigUint {
+	fn cmp(&self, other: &Self) -> Ordering {
+		self.limbs
+			.len()
+			.cmp(&other.limbs.len())
+			.then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+	}
+}
+
+impl PartialOrd for BigUint {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Add for &BigUint {
+	type Output = BigUint;
+
+	fn add(self, other: &BigUint) -> BigUint {
+		let len = self.limbs.len().max(other.limbs.len());
+		let mut limbs = Vec::with_capacity(len + 1);
+		let mut carry = 0u64;
+
+		for index in 0..len {
+			let sum = self.limbs.get(index).copied().unwrap_or(0) as u64
+				+ other.limbs.get(index).copied().unwrap_or(0) as u64
+				+ carry;
+			limbs.push(sum as u32);
+			carry = sum >> 32;
+		}
+		limbs.push(carry as u32);
+
+		BigUint::normalized(limbs)
+	}
+}
+
+impl Mul for &BigUint {
+	type Output = BigUint;
+
+	fn mul(self, other: &BigUint) -> BigUint {
+		let mut limbs = vec![0u32; self.limbs.len() + other.limbs.len()];
+
+		for (i, &a) in self.limbs.iter().enumerate() {
+			let mut carry = 0u64;
+			for (j, &b) in other.limbs.iter().enumerate() {
+				let product = a as u64 * b as u64 + limbs[i + j] as u64 + carry;
+				limbs[i + j] = product as u32;
+				carry = product >> 32;
+			}
+			limbs[i + other.limbs.len()] = carry as u32;
+		}
+
+		BigUint::normalized(limbs)
+	}
+}
+
+impl Display for BigUint {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		const CHUNK: u32 = 1_000_000_000;
+
+		let mut chunks = Vec::new();
+		let mut rest = self.clone();
+		while !rest.is_zero() {
+			let (quotient, chunk) = rest.div_rem_small(CHUNK);
+			chunks.push(chunk);
+			rest = quotient;
+		}
+
+		match chunks.split_last() {
+			None => write!(f, "0"),
+			Some((first, rest)) => {
+				write!(f, "{first}")?;
+				rest.iter()
+					.rev()
+					.try_for_each(|chunk| write!(f, "{chunk:09}"))
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const TWO_TO_128: &str = "340282366920938463463374607431768211456";
+	const TWO_TO_256: &str = "115792089237316195423570985008687907853269984665640564039457584007913129639936";
+
+	#[test]
+	fn arithmetic_matches_u128() {
+		let values = [0u128, 1, 7, u32::MAX as u128, 1 << 63, 123456789012345];
+
+		for &a in &values {
+			for &b in &values {
+				let (x, y) = (BigUint::from(a), BigUint::from(b));
+				assert_eq!((&x + &y).to_u128(), Some(a + b));
+				assert_eq!((&x * &y).to_u128(), Some(a * b));
+				assert_eq!(x.cmp(&y), a.cmp(&b));
+				assert_eq!(x.to_string(), a.to_string());
+
+				let quotient = x
+					.div_rem(&y)
+					.and_then(|(q, r)| Some((q.to_u128()?, r.to_u128()?)));
+				assert_eq!(quotient, a.checked_div(b).zip(a.checked_rem(b)));
+			}
+		}
+	}
+
+	#[test]
+	fn beyond_u128() {
+		let max = BigUint::from(u128::MAX);
+		let two_to_128 = &max + &BigUint::from(1);
+		assert_eq!(two_to_128.to_string(), TWO_TO_128);
+		assert_eq!(two_to_128.to_u128(), None);
+		assert_eq!(two_to_128.bits(), 129);
+
+		let two_to_256 = &two_to_128 * &two_to_128;
+		assert_eq!(two_to_256.to_string(), TWO_TO_256);
+
+		let (quotient, remainder) = (&two_to_256 + &BigUint::from(5))
+			.div_rem(&two_to_128)
+			.unwrap();
+		assert_eq!(quotient, two_to_128);
+		assert_eq!(remainder.to_u128(), Some(5));
+		assert_eq!(two_to_256.div_rem(&BigUint::zero()), None);
+
+		let a = &two_to_128 * &BigUint::from(6);
+		let b = &two_to_128 * &BigUint::from(15);
+		assert_eq!(a.gcd(&b), &two_to_128 * &BigUint::from(3));
+	}
+}
@@ -0,0 +1,523 @@
+use anyhow::{anyhow, bail, Context, Result};
+use aoc::{
+	check, cli,
+	inputs::{self, Key},
+	stream::Repeat,
+};
+use std::{
+	collections::HashMap,
+	env, fs,
+	io::{self, BufWriter, ErrorKind},
+	path::{Path, PathBuf},
+	process::Command,
+};
+
+const USAGE: &str =
+	"Usage: aoc [--strict] [--profile=NAME] check-input [DAY...]
+       aoc [--strict] matrix [DAY...]
+       aoc [--profile=NAME] inputs encrypt|decrypt [DAY...]
+       aoc inputs key
+       aoc repeat COUNT LINE
+
+`check-input` runs the day binaries built next to this one, so build them
+first with `cargo build --bins`. Days are given as `5`, `05`, `08_2` or
+`day_10`. With `--strict`, parsers reject malformed text instead of
+skipping it.
+
+`--profile=NAME` uses the inputs in `inputs/NAME/` rather than `inputs/`,
+which is the `default` profile. `matrix` runs every day on every profile
+and compares the answers with those in each profile's `answers.txt`, lines
+of a day and its answers like `day_05 379811651 27992443`.
+
+`inputs encrypt` writes `inputs/day_NN.txt.enc` next to each input, and
+`inputs decrypt` turns them back into plain text. The key is 64 hex digits
+in $AOC_INPUT_KEY, or in the file named by $AOC_INPUT_KEY_FILE, which
+defaults to `.aoc-key`. `inputs key` prints a new one.
+
+`repeat` writes LINE COUNT times to stdout, to make inputs of any size for
+the days that stream theirs.";
+
+/// The day binaries next to this one, sorted by name.
+fn days() -> Result<Vec<(String, PathBuf)>> {
+	let exe = env::current_exe()?;
+	let directory = exe
+		.parent()
+		.ok_or_else(|| anyhow!("Runner has no parent directory"))?;
+
+	let mut days: Vec<_> = fs::read_dir(directory)?
+		.filter_map(|entry| {
+			let path = entry.ok()?.path();
+			let name = path.file_name()?.to_str()?;
+			let name = name.strip_suffix(env::consts::EXE_SUFFIX)?;
+			(name.starts_with("day_") && !name.contains('.') && path.is_file())
+				.then(|| (name.to_string(), path.clone()))
+		})
+		.collect();
+	days.sort();
+
+	if days.is_empty() {
+		bail!("No day binaries found\n\n{USAGE}");
+	}
+
+	Ok(days)
+}
+
+/// The days matching any of `selected`, or all of them if none are.
+fn select(
+	days: Vec<(String, PathBuf)>,
+	selected: &[String],
+) -> Result<Vec<(String, PathBuf)>> {
+	if selected.is_empty() {
+		return Ok(days);
+	}
+
+	let prefixes: Vec<String> = selected
+		.iter()
+		.map(|day| {
+			let day = day.trim_start_matches("day_");
+			match day.len() {
+				1 => format!("day_0{day}"),
+				_ => format!("day_{day}"),
+			}
+		})
+		.collect();
+
+	for (day, prefix) in selected.iter().zip(&prefixes) {
+		if !days.iter().any(|(name, _)| matches(name, prefix)) {
+			bail!("Unknown day `{day}`\n\n{USAGE}");
+		}
+	}
+
+	Ok(days
+		.into_iter()
+		.filter(|(name, _)| prefixes.iter().any(|prefix| matches(name, prefix)))
+		.collect())
+}
+
+/// `day_08` matches both parts of the day, `day_08_2` only the second.
+fn matches(name: &str, prefix: &str) -> bool {
+	name == prefix
+		|| name
+			.strip_prefix(prefix)
+			.is_some_and(|rest| rest.starts_with('_'))
+}
+
+fn check_inputs(selected: &[String]) -> Result<()> {
+	let days = select(days()?, selected)?;
+	let flags: Vec<String> = cli::flags().collect();
+	let mut failed = Vec::new();
+
+	for (name, path) in &days {
+		let status = Command::new(path)
+			.arg(check::COMMAND)
+			.args(&flags)
+			.status()?;
+		if !status.success() {
+			failed.push(name.as_str());
+		}
+	}
+
+	if !failed.is_empty() {
+		bail!(
+			"{} of {} days rejected their input: {}",
+			failed.len(),
+			days.len(),
+			failed.join(", ")
+		);
+	}
+
+	println!("All {} inputs pass", days.len());
+
+	Ok(())
+}
+
+/// How a day did on one profile's input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Outcome {
+	/// The profile has no input for the day.
+	NoInput,
+	/// It ran, but the profile has no expected answers for the day.
+	Unchecked(Vec<String>),
+	Correct,
+	Wrong {
+		expected: Vec<String>,
+		actual: Vec<String>,
+	},
+	/// It exited with an error, given by the start of its stderr.
+	Failed(String),
+}
+
+impl Outcome {
+	fn label(&self) -> &'static str {
+		match self {
+			Outcome::NoInput => "-",
+			Outcome::Unchecked(_) => "?",
+			Outcome::Correct => "ok",
+			Outcome::Wrong { .. } => "WRONG",
+			Outcome::Failed(_) => "FAILED",
+		}
+	}
+
+	/// What went wrong, or what to add to `answers.txt`.
+	fn detail(&self) -> Option<String> {
+		match self {
+			Outcome::NoInput | Outcome::Correct => None,
+			Outcome::Unchecked(actual) => {
+				Some(format!("no expected answer, got {}", actual.join(" ")))
+			}
+			Outcome::Wrong { expected, actual } => Some(format!(
+				"expected {}, got {}",
+				expected.join(" "),
+				actual.join(" ")
+			)),
+			Outcome::Failed(reason) => Some(reason.clone()),
+		}
+	}
+
+	fn is_failure(&self) -> bool {
+		matches!(self, Outcome::Wrong { .. } | Outcome::Failed(_))
+	}
+}
+
+/// The `default` profile in `inputs/`, then one per subdirectory.
+fn profiles() -> Result<Vec<String>> {
+	let directory = inputs::directory(inputs::DEFAULT_PROFILE);
+	let mut profiles: Vec<_> = fs::read_dir(&directory)
+		.with_context(|| format!("Can't list `{directory}`"))?
+		.filter_map(|entry| {
+			let entry = entry.ok()?;
+			entry.path().is_dir().then_some(())?;
+			entry.file_name().into_string().ok()
+		})
+		.collect();
+	profiles.sort();
+	profiles.insert(0, inputs::DEFAULT_PROFILE.to_string());
+
+	Ok(profiles)
+}
+
+/// Answers from an `answers.txt`, by day. `#` starts a comment.
+fn parse_answers(text: &str) -> HashMap<String, Vec<String>> {
+	text.lines()
+		.filter_map(|line| {
+			let line = line.split('#').next()?;
+			let mut words = line.split_whitespace().map(String::from);
+			Some((words.next()?, words.collect()))
+		})
+		.collect()
+}
+
+fn expected_answers(profile: &str) -> Result<HashMap<String, Vec<String>>> {
+	let path = format!("{}/answers.txt", inputs::directory(profile));
+	match fs::read_to_string(&path) {
+		Ok(text) => Ok(parse_answers(&text)),
+		Err(error) if error.kind() == ErrorKind::NotFound => Ok(HashMap::new()),
+		Err(error) => {
+			Err(error).with_context(|| format!("Can't read `{path}`"))
+		}
+	}
+}
+
+/// The answers a day printed: the last word of each line, so `Part 1: 42`
+/// gives `42`.
+fn answers_in(output: &str) -> Vec<String> {
+	output
+		.lines()
+		.filter_map(|line| line.split_whitespace().last())
+		.map(String::from)
+		.collect()
+}
+
+/// Runs the day binary `name` on a profile's input.
+fn run(
+	name: &str,
+	path: &Path,
+	profile: &str,
+	flags: &[String],
+	expected: Option<&Vec<String>>,
+) -> Result<Outcome> {
+	// Both parts of a day, like `day_08_1` and `day_08_2`, share `day_08`.
+	let day = name
+		.strip_prefix("day_")
+		.and_then(|day| day.split('_').next())
+		.ok_or_else(|| anyhow!("`{name}` isn't a day binary"))?;
+	let input = format!("{}/day_{day}.txt", inputs::directory(profile));
+	if !inputs::exists(&input) {
+		return Ok(Outcome::NoInput);
+	}
+
+	let output = Command::new(path)
+		.arg(format!("--profile={profile}"))
+		.args(flags)
+		.output()?;
+	if !output.status.success() {
+		let stderr = String::from_utf8_lossy(&output.stderr);
+		let reason: Vec<_> = stderr
+			.lines()
+			.map(str::trim)
+			.filter(|line| !line.is_empty())
+			.take(2)
+			.collect();
+		return Ok(Outcome::Failed(match reason.is_empty() {
+			true => format!("exited with {}", output.status),
+			false => reason.join(" "),
+		}));
+	}
+
+	let actual = answers_in(&String::from_utf8_lossy(&output.stdout));
+	Ok(match expected {
+		None => Outcome::Unchecked(actual),
+		Some(expected) if *expected == actual => Outcome::Correct,
+		Some(expected) => Outcome::Wrong {
+			expected: expected.clone(),
+			actual,
+		},
+	})
+}
+
+/// Runs every selected day on every profile and prints a table of how
+/// each did, followed by what went wrong.
+fn matrix(selected: &[String]) -> Result<()> {
+	let days = select(days()?, selected)?;
+	let profiles = profiles()?;
+	let flags: Vec<String> = cli::flags()
+		.filter(|flag| !flag.starts_with("--profile"))
+		.collect();
+
+	let answers = profiles
+		.iter()
+		.map(|profile| expected_answers(profile))
+		.collect::<Result<Vec<_>>>()?;
+
+	let mut rows = Vec::new();
+	for (name, path) in &days {
+		let mut row = Vec::new();
+		for (profile, expected) in profiles.iter().zip(&answers) {
+			row.push(run(name, path, profile, &flags, expected.get(name))?);
+		}
+		rows.push(row);
+	}
+
+	let first = days.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+	print!("{:first$}", "");
+	for profile in &profiles {
+		print!("  {:>6}", profile);
+	}
+	println!();
+	for ((name, _), row) in days.iter().zip(&rows) {
+		print!("{name:first$}");
+		for (profile, outcome) in profiles.iter().zip(row) {
+			print!(
+				"  {:>width$}",
+				outcome.label(),
+				width = profile.len().max(6)
+			);
+		}
+		println!();
+	}
+
+	let mut failures = 0;
+	let mut details = Vec::new();
+	for ((name, _), row) in days.iter().zip(&rows) {
+		for (profile, outcome) in profiles.iter().zip(row) {
+			failures += outcome.is_failure() as usize;
+			if let Some(detail) = outcome.detail() {
+				details.push(format!("{name} on {profile}: {detail}"));
+			}
+		}
+	}
+	if !details.is_empty() {
+		println!("\n{}", details.join("\n"));
+	}
+
+	let runs: usize = rows
+		.iter()
+		.flatten()
+		.filter(|outcome| **outcome != Outcome::NoInput)
+		.count();
+	if failures > 0 {
+		bail!("{failures} of {runs} runs failed");
+	}
+	println!("\nAll {runs} runs passed");
+
+	Ok(())
+}
+
+/// The input of each selected day, or of every day with an input ending in
+/// `suffix` if none are.
+fn input_paths(selected: &[String], suffix: &str) -> Result<Vec<String>> {
+	let directory = inputs::directory(&inputs::profile()?);
+	if selected.is_empty() {
+		let mut paths: Vec<_> = fs::read_dir(&directory)
+			.with_context(|| format!("Can't list `{directory}`"))?
+			.filter_map(|entry| {
+				let name = entry.ok()?.file_name().into_string().ok()?;
+				let name = name.strip_suffix(suffix)?;
+				(name.starts_with("day_") && name.ends_with(".txt"))
+					.then(|| format!("{directory}/{name}"))
+			})
+			.collect();
+		paths.sort();
+		return Ok(paths);
+	}
+
+	selected
+		.iter()
+		.map(|day| {
+			let number = day.trim_start_matches("day_").split('_').next();
+			match number.and_then(|number| number.parse::<u32>().ok()) {
+				Some(number) => Ok(format!("{directory}/day_{number:02}.txt")),
+				None => bail!("Unknown day `{day}`\n\n{USAGE}"),
+			}
+		})
+		.collect()
+}
+
+/// Encrypts the inputs, leaving alone those whose encrypted copy is
+/// already up to date so they don't change with every run.
+fn encrypt_inputs(selected: &[String]) -> Result<()> {
+	let key = Key::load()?;
+
+	for path in input_paths(selected, "")? {
+		let input =
+			fs::read(&path).with_context(|| format!("Can't read `{path}`"))?;
+		let sealed_path = inputs::encrypted_path(&path);
+
+		let current = fs::read(&sealed_path)
+			.ok()
+			.and_then(|sealed| inputs::decrypt(&key, &path, &sealed).ok())
+			.is_some_and(|old| old == input);
+		if current {
+			println!("{sealed_path}: unchanged");
+			continue;
+		}
+
+		fs::write(&sealed_path, inputs::encrypt(&key, &path, &input))?;
+		println!("{sealed_path}: encrypted");
+	}
+
+	Ok(())
+}
+
+fn decrypt_inputs(selected: &[String]) -> Result<()> {
+	let key = Key::load()?;
+
+	for path in input_paths(selected, ".enc")? {
+		let sealed_path = inputs::encrypted_path(&path);
+		let sealed = fs::read(&sealed_path)
+			.with_context(|| format!("Can't read `{sealed_path}`"))?;
+		let input = inputs::decrypt(&key, &path, &sealed)
+			.with_context(|| format!("Can't decrypt `{sealed_path}`"))?;
+
+		fs::write(&path, input)?;
+		println!("{path}: decrypted");
+	}
+
+	Ok(())
+}
+
+fn inputs_command(args: &[String]) -> Result<()> {
+	match args.split_first() {
+		Some((action, days)) if action == "encrypt" => encrypt_inputs(days),
+		Some((action, days)) if action == "decrypt" => decrypt_inputs(days),
+		Some((action, [])) if action == "key" => {
+			println!("{}", Key::generate().to_hex());
+			Ok(())
+		}
+		_ => bail!(USAGE),
+	}
+}
+
+/// Writes a line over and over, for piping into a day or saving as input.
+fn repeat(args: &[String]) -> Result<()> {
+	let [count, line] = args else {
+		bail!(USAGE);
+	};
+	let count: usize = count
+		.parse()
+		.with_context(|| format!("Invalid count `{count}`"))?;
+
+	let mut lines = Repeat::new(format!("{line}\n"), count);
+	let mut stdout = BufWriter::new(io::stdout().lock());
+	match io::copy(&mut lines, &mut stdout) {
+		// The reader stopped early, like `head` does.
+		Err(error) if error.kind() == ErrorKind::BrokenPipe => Ok(()),
+		result => result.map(|_| ()).map_err(Into::into),
+	}
+}
+
+fn main() -> Result<()> {
+	let args: Vec<String> = cli::args().collect();
+
+	match args.split_first() {
+		Some((command, days)) if command == check::COMMAND => {
+			check_inputs(days)
+		}
+		Some((command, days)) if command == "matrix" => matrix(days),
+		Some((command, args)) if command == "inputs" => inputs_command(args),
+		Some((command, args)) if command == "repeat" => repeat(args),
+		_ => bail!(USAGE),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn selects_days() {
+		let days: Vec<_> = ["day_05", "day_08_1", "day_08_2", "day_10"]
+			.map(|name| (name.to_string(), PathBuf::from(name)))
+			.into();
+		let names = |selected: &[&str]| {
+			let selected: Vec<String> =
+				selected.iter().map(|day| day.to_string()).collect();
+			select(days.clone(), &selected).map(|days| {
+				days.into_iter().map(|(name, _)| name).collect::<Vec<_>>()
+			})
+		};
+
+		assert_eq!(names(&[]).unwrap().len(), 4);
+		assert_eq!(names(&["5", "day_10"]).unwrap(), ["day_05", "day_10"]);
+		assert_eq!(names(&["08"]).unwrap(), ["day_08_1", "day_08_2"]);
+		assert_eq!(names(&["08_2"]).unwrap(), ["day_08_2"]);
+		assert!(names(&["1"]).is_err());
+	}
+
+	#[test]
+	fn answers() {
+		let expected = parse_answers(
+			"# day_05 wasn't solved yet\nday_01_1 53194\n\nday_09 18 2 # old\n",
+		);
+		assert_eq!(expected.len(), 2);
+		assert_eq!(expected["day_01_1"], ["53194"]);
+		assert_eq!(expected["day_09"], ["18", "2"]);
+
+		assert_eq!(answers_in("Part 1: 18\nPart 2: 2\n"), ["18", "2"]);
+		assert_eq!(answers_in("Sum: 8022\n"), ["8022"]);
+		assert_eq!(profiles().unwrap()[0], inputs::DEFAULT_PROFILE);
+	}
+
+	#[test]
+	fn short_names() {
+		let path = Path::new("day");
+		assert!(run("day", path, inputs::DEFAULT_PROFILE, &[], None).is_err());
+	}
+
+	#[test]
+	fn input_paths_of_days() {
+		let selected = ["5", "08_2", "day_10"].map(String::from);
+		assert_eq!(
+			input_paths(&selected, "").unwrap(),
+			[
+				"inputs/day_05.txt",
+				"inputs/day_08.txt",
+				"inputs/day_10.txt"
+			]
+		);
+		assert!(input_paths(&["x".to_string()], "").is_err());
+		assert!(input_paths(&[], ".enc")
+			.unwrap()
+			.contains(&"inputs/day_01.txt".to_string()));
+	}
+}
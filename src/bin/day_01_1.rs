@@ -1,25 +1,70 @@
-use std::{
-	fs::File,
-	io::{self, BufRead},
+use anyhow::Result;
+use aoc::{
+	check::{self, Report},
+	diagnostic::Diagnostic,
+	inputs, parse,
+	stream::Input,
 };
 
-fn main() -> io::Result<()> {
-	let file = File::open("inputs/day_01.txt")?;
-	let reader = io::BufReader::new(file);
+/// Every line needs a digit to make a calibration value from.
+fn check(path: &str, input: &str) -> Report {
+	let mut report = Report::new(path, input);
+	for (number, line) in parse::lines(input) {
+		if !line.chars().any(|c| c.is_ascii_digit()) {
+			report.add(
+				Diagnostic::new("Line has no digits")
+					.at(number, 1)
+					.underline(line.len()),
+			);
+		}
+	}
+	report
+}
 
-	let sum: u32 = reader
-		.lines()
-		.map(|line| {
-			line.unwrap_or_default()
-				.chars()
-				.filter_map(|c| c.to_digit(10))
-				.collect::<Vec<_>>()
-		})
-		.filter(|digits| !digits.is_empty())
-		.map(|digits| digits[0] * 10 + digits[digits.len() - 1])
-		.sum();
+fn main() -> Result<()> {
+	if check::requested() {
+		let path = "inputs/day_01.txt";
+		let input = inputs::read_to_string(path)?;
+		check(&inputs::locate(path)?, &input).exit();
+	}
 
-	println!("{}", sum);
+	println!(
+		"{}",
+		calibration_sum(Input::from_args("inputs/day_01.txt")?)?
+	);
 
 	Ok(())
 }
+
+/// Sum of every line's first and last digit read as a two digit number.
+/// Streamed inputs can be big enough to overflow a `u32`.
+fn calibration_sum(input: Input) -> Result<u64> {
+	let mut sum = 0;
+
+	input.each_line(|line| {
+		let mut digits = line.chars().filter_map(|c| c.to_digit(10));
+		if let Some(first) = digits.next() {
+			sum += u64::from(first * 10 + digits.next_back().unwrap_or(first));
+		}
+		Ok(())
+	})?;
+
+	Ok(sum)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use aoc::stream::Repeat;
+	use std::io::BufReader;
+
+	/// Streams about 43 million lines, which takes a while in debug builds.
+	#[test]
+	#[ignore = "slow; run with `cargo test -- --ignored`"]
+	fn sums_past_u32() {
+		let lines = u32::MAX as usize / 99 + 1;
+		let input =
+			Input::new("repeat", BufReader::new(Repeat::new(b"9\n", lines)));
+		assert_eq!(calibration_sum(input).unwrap(), 99 * lines as u64);
+	}
+}
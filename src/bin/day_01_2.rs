@@ -1,6 +1,10 @@
-use std::{
-	fs::File,
-	io::{self, BufRead},
+use anyhow::Result;
+use aoc::{
+	check::{self, Report},
+	diagnostic::Diagnostic,
+	inputs,
+	parse::{self, ParseError},
+	stream::Input,
 };
 
 fn number_to_digit(number: &str) -> Option<u32> {
@@ -19,16 +23,49 @@ fn number_to_digit(number: &str) -> Option<u32> {
 	}
 }
 
-fn main() -> io::Result<()> {
-	let mut sum = 0;
+/// Every line needs a digit, written or spelled out.
+fn check(path: &str, input: &str) -> Report {
+	let mut report = Report::new(path, input);
+	for (number, line) in parse::lines(input) {
+		let has_digit = (0..line.len()).any(|i| {
+			line[i..].starts_with(|c: char| c.is_ascii_digit())
+				|| (3..=5).any(|j| {
+					line.get(i..i + j).and_then(number_to_digit).is_some()
+				})
+		});
+		if !has_digit {
+			report.add(
+				Diagnostic::new("Line has no digits")
+					.at(number, 1)
+					.underline(line.len()),
+			);
+		}
+	}
+	report
+}
+
+fn main() -> Result<()> {
+	if check::requested() {
+		let path = "inputs/day_01.txt";
+		let input = inputs::read_to_string(path)?;
+		check(&inputs::locate(path)?, &input).exit();
+	}
+
+	println!(
+		"{}",
+		calibration_sum(Input::from_args("inputs/day_01.txt")?)?
+	);
 
-	let file = File::open("inputs/day_01.txt")?;
-	let reader = io::BufReader::new(file);
+	Ok(())
+}
 
-	for line in reader.lines() {
-		let line = line.unwrap_or_default();
+/// Like part one, but digits can be spelled out.
+fn calibration_sum(input: Input) -> Result<u64> {
+	let mut sum: u64 = 0;
+	let mut digits: Vec<u32> = Vec::new();
 
-		let mut digits: Vec<u32> = Vec::new();
+	input.each_line(|line| {
+		digits.clear();
 
 		for i in 0..line.len() {
 			for j in 1..=5 {
@@ -51,14 +88,13 @@ fn main() -> io::Result<()> {
 			}
 		}
 
-		if digits.len() == 1 {
-			digits.push(digits[0]);
-		}
-
-		sum += digits[0] * 10 + digits[digits.len() - 1];
-	}
+		let (Some(first), Some(last)) = (digits.first(), digits.last()) else {
+			return Err(ParseError::new(1, 1, "Line has no digits"));
+		};
+		sum += u64::from(first * 10 + last);
 
-	println!("{}", sum);
+		Ok(())
+	})?;
 
-	Ok(())
+	Ok(sum)
 }
@@ -1,58 +1,120 @@
 use anyhow::Result;
-use regex::Regex;
-use std::{
-	fs::File,
-	io::{self, BufRead},
+use aoc::{
+	check::{self, Report},
+	inputs,
+	parse::{self, AocParse, ParseError},
+	stream::Input,
 };
+use std::str::FromStr;
 
 const MAX_VALUES: [u32; 3] = [12, 13, 14];
 
-fn color_index(color: &str) -> usize {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Color {
+	Red,
+	Green,
+	Blue,
+}
+
+impl FromStr for Color {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"red" => Ok(Color::Red),
+			"green" => Ok(Color::Green),
+			"blue" => Ok(Color::Blue),
+			_ => Err(format!("Unknown color `{s}`")),
+		}
+	}
+}
+
+#[derive(Debug, AocParse)]
+#[aoc(format = "{amount} {color}")]
+struct Cubes {
+	amount: u32,
+	color: Color,
+}
+
+#[derive(Debug, AocParse)]
+#[aoc(format = "{cubes}")]
+struct Round {
+	#[aoc(separator = ", ")]
+	cubes: Vec<Cubes>,
+}
+
+#[derive(Debug, AocParse)]
+#[aoc(format = "Game {id}: {rounds}")]
+struct Game {
+	id: u32,
+	#[aoc(separator = "; ")]
+	rounds: Vec<Round>,
+}
+
+impl Game {
+	fn cubes(&self) -> impl Iterator<Item = &Cubes> {
+		self.rounds.iter().flat_map(|round| &round.cubes)
+	}
+}
+
+fn color_index(color: Color) -> usize {
 	match color {
-		"red" => 0,
-		"green" => 1,
-		"blue" => 2,
-		_ => unreachable!(),
+		Color::Red => 0,
+		Color::Green => 1,
+		Color::Blue => 2,
+	}
+}
+
+/// Every line must be a game.
+fn check(path: &str, input: &str) -> Report {
+	let mut report = Report::new(path, input);
+	for (number, line) in parse::lines(input) {
+		report.parsed(line.parse::<Game>().map_err(|e| e.on_line(number)));
 	}
+	report
 }
 
 fn main() -> Result<()> {
-	let file = File::open("inputs/day_02.txt")?;
-	let reader = io::BufReader::new(file);
-
-	let re = Regex::new(r"(?P<amount>\d+)\s(?P<color>green|red|blue)")?;
-
-	let sum: u32 = reader
-		.lines()
-		.enumerate()
-		.filter_map(|(game, line)| {
-			let line = line.unwrap_or_default();
-
-			let valid_game = re.captures_iter(&line).all(|c| {
-				let amount = c["amount"]
-					.parse::<u32>()
-					.expect("Amount should be a number");
-				let color = &c["color"];
-
-				match color {
-					"red" | "green" | "blue" => {
-						amount <= MAX_VALUES[color_index(color)]
-					}
-					_ => false,
-				}
-			});
-
-			if valid_game {
-				Some((game + 1) as u32)
-			} else {
-				None
-			}
-		})
-		.collect::<Vec<u32>>()
-		.iter()
-		.sum();
-
-	println!("{}", sum);
+	if check::requested() {
+		let path = "inputs/day_02.txt";
+		let input = inputs::read_to_string(path)?;
+		check(&inputs::locate(path)?, &input).exit();
+	}
+
+	println!("{}", id_sum(Input::from_args("inputs/day_02.txt")?)?);
 
 	Ok(())
 }
+
+/// Sum of the ids of the possible games.
+fn id_sum(input: Input) -> Result<u64> {
+	let mut sum: u64 = 0;
+
+	input.each_line(|line| {
+		let game = line.parse::<Game>()?;
+		let possible = game
+			.cubes()
+			.all(|cubes| cubes.amount <= MAX_VALUES[color_index(cubes.color)]);
+		if possible {
+			sum = sum
+				.checked_add(game.id.into())
+				.ok_or_else(|| ParseError::new(1, 1, "Sum overflows"))?;
+		}
+		Ok(())
+	})?;
+
+	Ok(sum)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn large_ids() {
+		let games = "Game 4294967295: 1 red\nGame 13: 20 red\n\
+		             Game 4294967295: 2 blue, 3 green\n";
+		let input = Input::new("games", games.as_bytes());
+		assert_eq!(id_sum(input).unwrap(), 2 * u64::from(u32::MAX));
+	}
+}
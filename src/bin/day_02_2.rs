@@ -1,9 +1,11 @@
 use anyhow::Result;
-use regex::Regex;
-use std::{
-	fs::File,
-	io::{self, BufRead},
+use aoc::{
+	check::{self, Report},
+	inputs,
+	parse::{self, AocParse, ParseError},
+	stream::Input,
 };
+use std::str::FromStr;
 
 #[derive(Default)]
 struct MinCubes {
@@ -12,32 +14,115 @@ struct MinCubes {
 	b: u32,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Color {
+	Red,
+	Green,
+	Blue,
+}
+
+impl FromStr for Color {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"red" => Ok(Color::Red),
+			"green" => Ok(Color::Green),
+			"blue" => Ok(Color::Blue),
+			_ => Err(format!("Unknown color `{s}`")),
+		}
+	}
+}
+
+#[derive(Debug, AocParse)]
+#[aoc(format = "{amount} {color}")]
+struct Cubes {
+	amount: u32,
+	color: Color,
+}
+
+#[derive(Debug, AocParse)]
+#[aoc(format = "{cubes}")]
+struct Round {
+	#[aoc(separator = ", ")]
+	cubes: Vec<Cubes>,
+}
+
+#[derive(Debug, AocParse)]
+#[aoc(format = "Game {_}: {rounds}")]
+struct Game {
+	#[aoc(separator = "; ")]
+	rounds: Vec<Round>,
+}
+
+impl Game {
+	fn cubes(&self) -> impl Iterator<Item = &Cubes> {
+		self.rounds.iter().flat_map(|round| &round.cubes)
+	}
+}
+
+/// Every line must be a game.
+fn check(path: &str, input: &str) -> Report {
+	let mut report = Report::new(path, input);
+	for (number, line) in parse::lines(input) {
+		report.parsed(line.parse::<Game>().map_err(|e| e.on_line(number)));
+	}
+	report
+}
+
 fn main() -> Result<()> {
-	let file = File::open("inputs/day_02.txt")?;
-	let reader = io::BufReader::new(file);
+	if check::requested() {
+		let path = "inputs/day_02.txt";
+		let input = inputs::read_to_string(path)?;
+		check(&inputs::locate(path)?, &input).exit();
+	}
+
+	println!("{}", power_sum(Input::from_args("inputs/day_02.txt")?)?);
 
-	let re = Regex::new(r"(?P<amount>\d+)\s(?P<color>green|red|blue)")?;
-	let mut sum = 0;
+	Ok(())
+}
 
-	for (_game, line) in reader.lines().enumerate() {
-		let line = line.unwrap_or_default();
+/// Sum of the powers of the fewest cubes that make each game possible. A
+/// power alone can outgrow a `u64`.
+fn power_sum(input: Input) -> Result<u128> {
+	let mut sum: u128 = 0;
+
+	input.each_line(|line| {
+		let game = line.parse::<Game>()?;
 		let mut min_cubes = MinCubes::default();
 
-		for (_, [amount, color]) in re.captures_iter(&line).map(|c| c.extract())
-		{
-			let amount = amount.parse::<u32>()?;
+		for cubes in game.cubes() {
+			let amount = cubes.amount;
 
-			match color {
-				"red" => min_cubes.r = min_cubes.r.max(amount),
-				"green" => min_cubes.g = min_cubes.g.max(amount),
-				"blue" => min_cubes.b = min_cubes.b.max(amount),
-				_ => unreachable!(),
+			match cubes.color {
+				Color::Red => min_cubes.r = min_cubes.r.max(amount),
+				Color::Green => min_cubes.g = min_cubes.g.max(amount),
+				Color::Blue => min_cubes.b = min_cubes.b.max(amount),
 			}
 		}
-		sum += min_cubes.r * min_cubes.g * min_cubes.b;
-	}
+		let power = u128::from(min_cubes.r)
+			* u128::from(min_cubes.g)
+			* u128::from(min_cubes.b);
+		sum = sum
+			.checked_add(power)
+			.ok_or_else(|| ParseError::new(1, 1, "Sum overflows"))?;
 
-	println!("{}", sum);
+		Ok(())
+	})?;
 
-	Ok(())
+	Ok(sum)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn large_powers() {
+		let games = "Game 1: 4294967295 red, 2 green; 4294967295 blue\n\
+		             Game 2: 1 red, 1 green, 1 blue\n";
+		let input = Input::new("games", games.as_bytes());
+		let max = u128::from(u32::MAX);
+		assert_eq!(power_sum(input).unwrap(), max * 2 * max + 1);
+	}
 }
@@ -1,104 +1,91 @@
 use anyhow::Result;
-use std::{
-	fs::File,
-	io::{self, BufRead},
+use aoc::{
+	check::{self, Report},
+	inputs,
 };
 
-fn number_to_left(chars: &[char], index: usize) -> Result<u32> {
-	let mut number_chars = Vec::new();
+/// The run of digits ending just left of `index`.
+fn number_to_left(row: &[u8], index: usize) -> &[u8] {
+	let start = row[..index]
+		.iter()
+		.rposition(|b| !b.is_ascii_digit())
+		.map_or(0, |i| i + 1);
 
-	for i in (0..index).rev() {
-		let c = chars[i];
-
-		if c.is_ascii_digit() {
-			number_chars.push(c);
-		} else {
-			break;
-		}
-	}
-
-	if !number_chars.is_empty() {
-		number_chars.reverse();
-		let number = number_chars.iter().collect::<String>();
-		Ok(number.parse::<u32>()?)
-	} else {
-		Ok(0)
-	}
+	&row[start..index]
 }
 
-fn number_to_right(chars: &[char], index: usize) -> Result<u32> {
-	let mut number_chars = Vec::new();
+/// The run of digits starting just right of `index`.
+fn number_to_right(row: &[u8], index: usize) -> &[u8] {
+	let end = row[index + 1..]
+		.iter()
+		.position(|b| !b.is_ascii_digit())
+		.map_or(row.len(), |i| index + 1 + i);
 
-	for c in chars.iter().skip(index + 1) {
-		if c.is_ascii_digit() {
-			number_chars.push(*c);
-		} else {
-			break;
-		}
-	}
-
-	if !number_chars.is_empty() {
-		let number = number_chars.iter().collect::<String>();
-		Ok(number.parse::<u32>()?)
-	} else {
-		Ok(0)
-	}
+	&row[index + 1..end]
 }
 
-fn number_span(chars: &[char], index: usize) -> Result<u32> {
-	let mut left_index = index;
+/// The whole run of digits through `index`.
+fn number_span(row: &[u8], index: usize) -> &[u8] {
+	let start = index - number_to_left(row, index).len();
+	let end = index + 1 + number_to_right(row, index).len();
 
-	while left_index > 0 && chars[left_index - 1].is_ascii_digit() {
-		left_index -= 1;
-	}
+	&row[start..end]
+}
 
-	let mut right_index = index;
-	while right_index < chars.len() - 1
-		&& chars[right_index + 1].is_ascii_digit()
-	{
-		right_index += 1;
+/// The value of a run of digits, 0 for an empty one.
+fn value(digits: &[u8]) -> Result<u32> {
+	if digits.is_empty() {
+		return Ok(0);
 	}
 
-	let number = chars[left_index..=right_index].iter().collect::<String>();
+	Ok(std::str::from_utf8(digits)?.parse()?)
+}
 
-	Ok(number.parse::<u32>()?)
+/// The schematic must be rectangular, as symbols look at their neighbors
+/// on the rows above and below.
+fn check(path: &str, input: &str) -> Report {
+	let mut report = Report::new(path, input);
+	report.rectangular();
+	report
 }
 
 fn main() -> Result<()> {
-	let file = File::open("inputs/day_03.txt")?;
-	let reader = io::BufReader::new(file);
+	if check::requested() {
+		let path = "inputs/day_03.txt";
+		let input = inputs::read_to_string(path)?;
+		check(&inputs::locate(path)?, &input).exit();
+	}
 
-	let matrix: Vec<Vec<char>> = reader
-		.lines()
-		.map(|line| line.expect("Line to exist").chars().collect())
-		.collect();
+	let input = inputs::read_to_string("inputs/day_03.txt")?;
+	let matrix: Vec<&[u8]> = input.lines().map(str::as_bytes).collect();
 
 	let mut sum = 0;
 
 	for (row_index, row) in matrix.iter().enumerate() {
 		for (item_index, item) in row.iter().enumerate() {
-			if item == &'.' || item.is_ascii_digit() {
+			if *item == b'.' || item.is_ascii_digit() {
 				continue;
 			}
 
-			sum += number_to_left(row, item_index)?;
-			sum += number_to_right(row, item_index)?;
+			sum += value(number_to_left(row, item_index))?;
+			sum += value(number_to_right(row, item_index))?;
 
-			if let Some(top) = matrix.get(row_index - 1) {
+			let top = row_index.checked_sub(1).and_then(|i| matrix.get(i));
+			if let Some(top) = top {
 				if !top[item_index].is_ascii_digit() {
-					sum += number_to_left(top, item_index)?;
-					sum += number_to_right(top, item_index)?;
+					sum += value(number_to_left(top, item_index))?;
+					sum += value(number_to_right(top, item_index))?;
 				} else {
-					sum += number_span(top, item_index)?;
+					sum += value(number_span(top, item_index))?;
 				}
 			}
 
 			if let Some(bottom) = matrix.get(row_index + 1) {
 				if !bottom[item_index].is_ascii_digit() {
-					sum += number_to_left(bottom, item_index)?;
-					sum += number_to_right(bottom, item_index)?;
+					sum += value(number_to_left(bottom, item_index))?;
+					sum += value(number_to_right(bottom, item_index))?;
 				} else {
-					sum += number_span(bottom, item_index)?;
+					sum += value(number_span(bottom, item_index))?;
 				}
 			}
 		}
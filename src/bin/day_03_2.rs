@@ -1,113 +1,96 @@
 use anyhow::Result;
-use std::{
-	fs::File,
-	io::{self, BufRead},
+use aoc::{
+	check::{self, Report},
+	inputs,
 };
 
-fn number_to_left(chars: &[char], index: usize) -> Option<String> {
-	let mut number_chars = Vec::new();
+/// The run of digits ending just left of `index`.
+fn number_to_left(row: &[u8], index: usize) -> &[u8] {
+	let start = row[..index]
+		.iter()
+		.rposition(|b| !b.is_ascii_digit())
+		.map_or(0, |i| i + 1);
 
-	for i in (0..index).rev() {
-		let c = chars[i];
+	&row[start..index]
+}
 
-		if c.is_ascii_digit() {
-			number_chars.push(c);
-		} else {
-			break;
-		}
-	}
+/// The run of digits starting just right of `index`.
+fn number_to_right(row: &[u8], index: usize) -> &[u8] {
+	let end = row[index + 1..]
+		.iter()
+		.position(|b| !b.is_ascii_digit())
+		.map_or(row.len(), |i| index + 1 + i);
 
-	if !number_chars.is_empty() {
-		number_chars.reverse();
-		Some(number_chars.iter().collect::<String>())
-	} else {
-		None
-	}
+	&row[index + 1..end]
 }
 
-fn number_to_right(chars: &[char], index: usize) -> Option<String> {
-	let mut number_chars = Vec::new();
+/// The whole run of digits through `index`.
+fn number_span(row: &[u8], index: usize) -> &[u8] {
+	let start = index - number_to_left(row, index).len();
+	let end = index + 1 + number_to_right(row, index).len();
 
-	for c in chars.iter().skip(index + 1) {
-		if c.is_ascii_digit() {
-			number_chars.push(*c);
-		} else {
-			break;
-		}
-	}
+	&row[start..end]
+}
 
-	if !number_chars.is_empty() {
-		Some(number_chars.iter().collect::<String>())
+/// The digits left and right of `index`, or the number through it.
+fn numbers_around(row: &[u8], index: usize) -> [&[u8]; 2] {
+	if !row[index].is_ascii_digit() {
+		[number_to_left(row, index), number_to_right(row, index)]
 	} else {
-		None
+		[number_span(row, index), &[]]
 	}
 }
 
-fn number_span(chars: &[char], index: usize) -> Option<String> {
-	let mut left_index = index;
-
-	while left_index > 0 && chars[left_index - 1].is_ascii_digit() {
-		left_index -= 1;
-	}
-
-	let mut right_index = index;
-	while right_index < chars.len() - 1
-		&& chars[right_index + 1].is_ascii_digit()
-	{
-		right_index += 1;
-	}
-
-	let number = chars[left_index..=right_index].iter().collect::<String>();
-
-	Some(number)
+/// The schematic must be rectangular, as symbols look at their neighbors
+/// on the rows above and below.
+fn check(path: &str, input: &str) -> Report {
+	let mut report = Report::new(path, input);
+	report.rectangular();
+	report
 }
 
 fn main() -> Result<()> {
-	let file = File::open("inputs/day_03.txt")?;
-	let reader = io::BufReader::new(file);
+	if check::requested() {
+		let path = "inputs/day_03.txt";
+		let input = inputs::read_to_string(path)?;
+		check(&inputs::locate(path)?, &input).exit();
+	}
 
-	let matrix: Vec<Vec<char>> = reader
-		.lines()
-		.map(|line| line.expect("Line to exist").chars().collect())
-		.collect();
+	let input = inputs::read_to_string("inputs/day_03.txt")?;
+	let matrix: Vec<&[u8]> = input.lines().map(str::as_bytes).collect();
 
 	let mut sum = 0;
 
 	for (row_index, row) in matrix.iter().enumerate() {
 		for (item_index, item) in row.iter().enumerate() {
-			if item == &'.' || item.is_ascii_digit() || item != &'*' {
+			if *item != b'*' {
 				continue;
 			}
 
-			let mut adjacents: Vec<Option<String>> = vec![
+			let top = row_index.checked_sub(1).and_then(|i| matrix.get(i));
+			let bottom = matrix.get(row_index + 1);
+
+			let mut adjacents = [
 				number_to_left(row, item_index),
 				number_to_right(row, item_index),
-			];
-
-			if let Some(top) = matrix.get(row_index - 1) {
-				if !top[item_index].is_ascii_digit() {
-					adjacents.push(number_to_left(top, item_index));
-					adjacents.push(number_to_right(top, item_index));
-				} else {
-					adjacents.push(number_span(top, item_index));
-				}
-			}
-
-			if let Some(bottom) = matrix.get(row_index + 1) {
-				if !bottom[item_index].is_ascii_digit() {
-					adjacents.push(number_to_left(bottom, item_index));
-					adjacents.push(number_to_right(bottom, item_index));
-				} else {
-					adjacents.push(number_span(bottom, item_index));
-				}
-			}
-
-			let some_values: Vec<String> =
-				adjacents.into_iter().flatten().collect();
-
-			if some_values.len() == 2 {
-				let first = some_values[0].parse::<u32>()?;
-				let second = some_values[1].parse::<u32>()?;
+			]
+			.into_iter()
+			.chain(
+				top.into_iter()
+					.flat_map(|top| numbers_around(top, item_index)),
+			)
+			.chain(
+				bottom
+					.into_iter()
+					.flat_map(|bottom| numbers_around(bottom, item_index)),
+			)
+			.filter(|digits| !digits.is_empty());
+
+			if let (Some(first), Some(second), None) =
+				(adjacents.next(), adjacents.next(), adjacents.next())
+			{
+				let first = std::str::from_utf8(first)?.parse::<u32>()?;
+				let second = std::str::from_utf8(second)?.parse::<u32>()?;
 
 				sum += first * second;
 			}
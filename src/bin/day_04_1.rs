@@ -1,43 +1,99 @@
 use anyhow::Result;
-use std::{
-	fs::File,
-	io::{self, BufRead},
+use aoc::{
+	check::{self, Report},
+	diagnostic::Diagnostic,
+	inputs,
+	parse::{self, ParseError},
+	stream::Input,
 };
 
-fn main() -> Result<()> {
-	let file = File::open("inputs/day_04.txt")?;
-	let reader = io::BufReader::new(file);
+/// The winning numbers and the numbers you have on a
+/// `Card N: winning | numbers` line, or `None` for a line without a `|`,
+/// which is an error in strict mode unless the line is blank.
+fn card(line: &str) -> parse::Result<Option<(Vec<u8>, Vec<u8>)>> {
+	let Some((winning_numbers, numbers)) = line.split_once('|') else {
+		if !line.trim().is_empty() {
+			parse::skip(ParseError::new(
+				1,
+				1,
+				"Expected `|` between the two lists",
+			))?;
+		}
+		return Ok(None);
+	};
+	let (_, winning_numbers) = parse::labeled(winning_numbers)?;
 
-	let mut sum = 0;
+	Ok(Some((
+		parse::unsigned_integers(winning_numbers)?,
+		parse::unsigned_integers(numbers)?,
+	)))
+}
 
-	for line in reader.lines() {
-		let line = line?;
+/// Every line must be a card.
+fn check(path: &str, input: &str) -> Report {
+	let mut report = Report::new(path, input);
+	for (number, line) in parse::lines(input) {
+		if let Some(None) =
+			report.parsed(card(line).map_err(|e| e.on_line(number)))
+		{
+			report.add(
+				Diagnostic::new("Expected `|` between the two lists")
+					.at(number, 1)
+					.underline(line.len()),
+			);
+		}
+	}
+	report
+}
 
-		if let Some(pipe_index) = line.find('|') {
-			let winning_numbers: Vec<u8> = line[..pipe_index]
-				.split_whitespace()
-				.filter_map(|num_str| num_str.parse().ok())
-				.collect();
+fn main() -> Result<()> {
+	if check::requested() {
+		let path = "inputs/day_04.txt";
+		let input = inputs::read_to_string(path)?;
+		check(&inputs::locate(path)?, &input).exit();
+	}
 
-			let numbers: Vec<u8> = line[pipe_index + 1..]
-				.split_whitespace()
-				.filter_map(|num_str| num_str.parse().ok())
-				.collect();
+	println!("{}", points(Input::from_args("inputs/day_04.txt")?)?);
 
+	Ok(())
+}
+
+/// Total points of the cards, each worth 1 for its first match and doubled
+/// for every match after that.
+fn points(input: Input) -> Result<u64> {
+	let mut sum: u64 = 0;
+
+	input.each_line(|line| {
+		if let Some((winning_numbers, numbers)) = card(line)? {
 			let count_in_winning = numbers
 				.iter()
 				.filter(|&n| winning_numbers.contains(n))
 				.count();
 
-			sum += if count_in_winning == 0 {
-				0
-			} else {
-				2u32.pow(count_in_winning as u32 - 1)
+			let points = match count_in_winning {
+				0 => Some(0),
+				count => 1u64.checked_shl(count as u32 - 1),
 			};
+			sum = points
+				.and_then(|points| sum.checked_add(points))
+				.ok_or_else(|| ParseError::new(1, 1, "Sum overflows"))?;
 		}
-	}
+		Ok(())
+	})?;
 
-	println!("{}", sum);
+	Ok(sum)
+}
 
-	Ok(())
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn large_points() {
+		let numbers: Vec<String> = (1..=33).map(|n| n.to_string()).collect();
+		let numbers = numbers.join(" ");
+		let cards = format!("Card 1: {numbers} | {numbers}\nCard 2: 1 | 1\n");
+		let input = Input::new("cards", std::io::Cursor::new(cards));
+		assert_eq!(points(input).unwrap(), u64::from(u32::MAX) + 2);
+	}
 }
@@ -1,30 +1,110 @@
 use anyhow::Result;
-use std::{
-	collections::HashMap,
-	fs::File,
-	io::{self, BufRead},
+use aoc::{
+	animation::{Frame, Player},
+	answer::Answer,
+	check::{self, Report},
+	cli,
+	diagnostic::Diagnostic,
+	inputs,
+	math::{self, MathError},
+	parse::{self, ParseError},
+	render::Rgb,
+	stream::Input,
 };
 
-fn main() -> Result<()> {
-	let file = File::open("inputs/day_04.txt")?;
-	let mut card_scores: Vec<u8> = Vec::new();
+/// One frame per scored card: every card's copy count, with the card being
+/// scored marked and the cards it wins copies of highlighted. Fails if a
+/// count doesn't fit in a `u64`.
+fn cascade(card_scores: &[u8]) -> math::Result<Vec<Frame>> {
+	let mut copies = vec![1u64; card_scores.len()];
+	let mut frames = Vec::with_capacity(card_scores.len());
 
-	for line in io::BufReader::new(file).lines() {
-		let line = line?;
+	for (index, &score) in card_scores.iter().enumerate() {
+		let won = index + 1..(index + 1 + score as usize).min(copies.len());
+		for card in won.clone() {
+			copies[card] = copies[card]
+				.checked_add(copies[index])
+				.ok_or(MathError::Overflow)?;
+		}
 
-		if let Some(pipe_index) = line.find('|') {
-			let (winning_numbers, numbers) = line.split_at(pipe_index);
+		let lines = copies.iter().enumerate().map(|(card, &count)| {
+			let marker = match card {
+				card if card == index => '>',
+				card if won.contains(&card) => '+',
+				_ => ' ',
+			};
+			format!(
+				"{marker} Card {:>3} {:>8} {}",
+				card + 1,
+				count,
+				"#".repeat(count.ilog2() as usize + 1)
+			)
+		});
 
-			let winning_numbers: Vec<u8> = winning_numbers
-				.split_whitespace()
-				.filter_map(|num_str| num_str.parse().ok())
-				.collect();
+		frames.push(Frame::text(
+			lines,
+			format!(
+				"Card {} adds {} copies to each of the next {} cards",
+				index + 1,
+				copies[index],
+				score
+			),
+		));
+	}
 
-			let numbers: Vec<u8> = numbers[1..]
-				.split_whitespace()
-				.filter_map(|num_str| num_str.parse().ok())
-				.collect();
+	Ok(frames)
+}
+
+/// The winning numbers and the numbers you have on a
+/// `Card N: winning | numbers` line, or `None` for a line without a `|`,
+/// which is an error in strict mode unless the line is blank.
+fn card(line: &str) -> parse::Result<Option<(Vec<u8>, Vec<u8>)>> {
+	let Some((winning_numbers, numbers)) = line.split_once('|') else {
+		if !line.trim().is_empty() {
+			parse::skip(ParseError::new(
+				1,
+				1,
+				"Expected `|` between the two lists",
+			))?;
+		}
+		return Ok(None);
+	};
+	let (_, winning_numbers) = parse::labeled(winning_numbers)?;
+
+	Ok(Some((
+		parse::unsigned_integers(winning_numbers)?,
+		parse::unsigned_integers(numbers)?,
+	)))
+}
+
+/// Every line must be a card.
+fn check(path: &str, input: &str) -> Report {
+	let mut report = Report::new(path, input);
+	for (number, line) in parse::lines(input) {
+		if let Some(None) =
+			report.parsed(card(line).map_err(|e| e.on_line(number)))
+		{
+			report.add(
+				Diagnostic::new("Expected `|` between the two lists")
+					.at(number, 1)
+					.underline(line.len()),
+			);
+		}
+	}
+	report
+}
 
+fn main() -> Result<()> {
+	if check::requested() {
+		let path = "inputs/day_04.txt";
+		let input = inputs::read_to_string(path)?;
+		check(&inputs::locate(path)?, &input).exit();
+	}
+
+	let mut card_scores: Vec<u8> = Vec::new();
+
+	Input::open("inputs/day_04.txt")?.each_line(|line| {
+		if let Some((winning_numbers, numbers)) = card(line)? {
 			let score = numbers
 				.iter()
 				.filter(|&n| winning_numbers.contains(n))
@@ -32,27 +112,47 @@ fn main() -> Result<()> {
 
 			card_scores.push(score);
 		}
+		Ok(())
+	})?;
+
+	if cli::args().next().as_deref() == Some("animate") {
+		return Player::new(cascade(&card_scores)?)
+			.fps(5.0)
+			.palette(|c| match c {
+				'>' => Some(Rgb::YELLOW),
+				'+' => Some(Rgb::GREEN),
+				'#' => Some(Rgb::BLUE),
+				_ => None,
+			})
+			.play();
 	}
 
-	let store = (0..card_scores.len())
-		.map(|index| (index, 1))
-		.collect::<HashMap<usize, u32>>();
+	let mut copies = vec![Answer::from(1u64); card_scores.len()];
 
-	let result = card_scores
-		.iter()
-		.enumerate()
-		.fold(store, |mut acc, (index, card_score)| {
-			for i in (index + 1)..(index + 1 + *card_score as usize) {
-				let to_add = *acc.get(&index).unwrap();
-				acc.entry(i).and_modify(|value| *value += to_add);
-			}
+	for (index, &card_score) in card_scores.iter().enumerate() {
+		let won =
+			(index + 1)..(index + 1 + card_score as usize).min(copies.len());
+		for i in won {
+			copies[i] = copies[i].checked_add(&copies[index])?;
+		}
+	}
 
-			acc
-		})
-		.values()
-		.sum::<u32>();
+	let result = Answer::sum(&copies)?;
 
-	println!("{:#?}", result);
+	println!("{}", result);
 
 	Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn cascade_overflow() {
+		// Each card wins a copy of every later card, doubling the count.
+		let scores: Vec<u8> = (0..70u8).rev().collect();
+		assert_eq!(cascade(&scores[6..]).unwrap().len(), 64);
+		assert_eq!(cascade(&scores).unwrap_err(), MathError::Overflow);
+	}
+}
@@ -1,11 +1,15 @@
 use anyhow::Result;
-use std::{
-	fs::File,
-	io::{BufRead, BufReader},
-	ops::{Index, IndexMut, Range},
+use aoc::{
+	check::{self, Report},
+	diagnostic::{Diagnose, Diagnostic},
+	inputs,
+	parse::{self, ParseError},
+	piecewise::PiecewiseMap,
+	range::RangeSet,
 };
+use std::ops::{Index, IndexMut};
 
-type Seed = u64;
+type Seed = i64;
 
 const MAP_TYPES: [&str; 7] = [
 	"seed-to-soil map:",
@@ -17,66 +21,21 @@ const MAP_TYPES: [&str; 7] = [
 	"humidity-to-location map:",
 ];
 
-#[derive(Default, Debug)]
-struct Entry {
-	destination_range: Range<Seed>,
-	source_range: Range<Seed>,
-}
-
-impl Entry {
-	fn new(destination_range: Range<Seed>, source_range: Range<Seed>) -> Self {
-		Self {
-			destination_range,
-			source_range,
-		}
-	}
-
-	fn contains_source(&self, source: Seed) -> bool {
-		self.source_range.contains(&source)
-	}
-}
-
-#[derive(Default, Debug)]
-struct Map {
-	entries: Vec<Entry>,
-}
-
-impl Map {
-	fn new(entries: Vec<Entry>) -> Self {
-		Self { entries }
-	}
-
-	fn get(&self, source: Seed) -> Seed {
-		self.entries
-			.iter()
-			.find_map(|entry| {
-				if entry.contains_source(source) {
-					Some(
-						entry.destination_range.start
-							+ (source - entry.source_range.start),
-					)
-				} else {
-					None
-				}
-			})
-			.unwrap_or(source)
-	}
-}
-
 #[derive(Default, Debug)]
 struct Almanac {
 	seeds: Vec<Seed>,
-	seed_to_soil: Map,
-	soil_to_fertilizer: Map,
-	ferilizer_to_water: Map,
-	water_to_light: Map,
-	light_to_temperature: Map,
-	temperature_to_humidity: Map,
-	humidity_to_location: Map,
+	seed_to_soil: PiecewiseMap,
+	soil_to_fertilizer: PiecewiseMap,
+	ferilizer_to_water: PiecewiseMap,
+	water_to_light: PiecewiseMap,
+	light_to_temperature: PiecewiseMap,
+	temperature_to_humidity: PiecewiseMap,
+	humidity_to_location: PiecewiseMap,
+	seed_to_location: PiecewiseMap,
 }
 
 impl Index<usize> for Almanac {
-	type Output = Map;
+	type Output = PiecewiseMap;
 
 	fn index(&self, index: usize) -> &Self::Output {
 		match index {
@@ -108,71 +67,73 @@ impl IndexMut<usize> for Almanac {
 }
 
 impl Almanac {
-	fn from_file(path: &str) -> Self {
-		let file = File::open(path).unwrap();
-		let reader = BufReader::new(file);
+	fn from_file(path: &str) -> Result<Self> {
+		let input = inputs::read_to_string(path)?;
+		Ok(Self::parse(&input).diagnose(path, &input)?)
+	}
 
+	fn parse(input: &str) -> parse::Result<Self> {
 		let mut almanac = Almanac::default();
 
-		let mut iter = reader.lines().peekable();
-
-		while let Some(Ok(line)) = iter.next() {
-			let line = line.trim();
-			if line.starts_with("seeds:") {
-				almanac.seeds = line
-					.split_whitespace()
-					.skip(1)
-					.filter_map(|s| s.parse().ok())
-					.collect();
-			} else {
-				for (index, map_type) in MAP_TYPES.into_iter().enumerate() {
-					if line.starts_with(map_type) {
-						let mut parameters = Vec::new();
-
-						while let Some(Ok(line)) = iter.next() {
-							let line = line.trim();
-
-							if line.is_empty() || MAP_TYPES.contains(&line) {
-								break;
-							}
-
-							let seeds: Vec<Seed> = line
-								.split_whitespace()
-								.filter_map(|s| s.parse().ok())
-								.collect();
-
-							if seeds.len() == 3 {
-								let destination_range = seeds[0];
-								let source_range = seeds[1];
-								let range_length = seeds[2];
-
-								let parameter = Entry::new(
-									destination_range
-										..(destination_range + range_length),
-									source_range..(source_range + range_length),
-								);
-								parameters.push(parameter);
-							}
-						}
-
-						let map = Map::new(parameters);
-						almanac[index] = map;
+		for section in parse::sections(input) {
+			let mut lines = section.lines();
+			let Some((number, header)) = lines.next() else {
+				continue;
+			};
+
+			if header.starts_with("seeds:") {
+				almanac.seeds = parse::labeled_integers(header, "seeds")
+					.map_err(|e| e.on_line(number))?;
+				if !almanac.seeds.len().is_multiple_of(2) {
+					return Err(ParseError::new(
+						number,
+						1,
+						"Odd number of seeds, which come in `start length` \
+						 pairs",
+					));
+				}
+				continue;
+			}
+
+			let index = MAP_TYPES
+				.iter()
+				.position(|&map_type| header.trim() == map_type)
+				.ok_or_else(|| {
+					ParseError::new(
+						number,
+						1,
+						format!("Unknown map `{header}`"),
+					)
+				})?;
+
+			let mut parameters = Vec::new();
+			for (number, line) in lines {
+				match parse::integers(line).map_err(|e| e.on_line(number))?[..]
+				{
+					[destination, source, length] => {
+						parameters.push((destination, source, length))
 					}
+					_ => parse::skip(ParseError::new(
+						number,
+						1,
+						"Expected `destination source length`",
+					))?,
 				}
 			}
+
+			almanac[index] = PiecewiseMap::from_triples(parameters);
 		}
 
-		almanac
+		almanac.seed_to_location = (0..MAP_TYPES.len())
+			.fold(PiecewiseMap::identity(), |map, index| {
+				map.compose(&almanac[index])
+			});
+
+		Ok(almanac)
 	}
 
 	fn location_from_seed(&self, seed: Seed) -> Seed {
-		let soil = self.seed_to_soil.get(seed);
-		let fertilizer = self.soil_to_fertilizer.get(soil);
-		let water = self.ferilizer_to_water.get(fertilizer);
-		let light = self.water_to_light.get(water);
-		let temperature = self.light_to_temperature.get(light);
-		let humidity = self.temperature_to_humidity.get(temperature);
-		self.humidity_to_location.get(humidity)
+		self.seed_to_location.get(seed)
 	}
 
 	fn lowest_location_from_seeds(&self) -> Seed {
@@ -187,23 +148,82 @@ impl Almanac {
 	}
 
 	fn lowest_location_from_seeds_range(&self) -> Seed {
-		let mut lowest: Seed = Seed::MAX;
+		let seeds: RangeSet<Seed> = self
+			.seeds
+			.chunks_exact(2)
+			.map(|chunk| chunk[0]..(chunk[0] + chunk[1]))
+			.collect();
+
+		self.seed_to_location
+			.image(&seeds)
+			.min()
+			.unwrap_or(Seed::MAX)
+	}
+}
 
-		let chunks: Vec<_> = self.seeds.chunks(2).collect();
+/// The almanac must parse, which needs the seeds to pair up into ranges,
+/// and the source ranges of a map must not overlap, or a value would have
+/// two images.
+fn check(path: &str, input: &str) -> Report {
+	let mut report = Report::new(path, input);
+	if report.parsed(Almanac::parse(input)).is_none() {
+		return report;
+	}
 
-		for chunk in chunks {
-			let seed_range = chunk[0]..(chunk[0] + chunk[1]);
-			for seed in seed_range {
-				let location = self.location_from_seed(seed);
-				lowest = lowest.min(location);
+	for section in parse::sections(input) {
+		let mut lines = section.lines();
+		let Some((_, header)) = lines.next() else {
+			continue;
+		};
+		if header.starts_with("seeds:") {
+			continue;
+		}
+
+		// Start, end, line and column of each source range.
+		let mut sources: Vec<(Seed, Seed, usize, usize)> = lines
+			.filter_map(|(number, line)| {
+				let [_, source, length] =
+					parse::integers::<Seed>(line).ok()?[..]
+				else {
+					return None;
+				};
+				let column = line.find(char::is_whitespace)? + 2;
+				Some((source, source + length, number, column))
+			})
+			.collect();
+		sources.sort();
+
+		// The furthest reaching range so far, and its line.
+		let mut reach: Option<(Seed, usize)> = None;
+		for (start, end, number, column) in sources {
+			match reach {
+				Some((furthest, line)) if start < furthest => report.add(
+					Diagnostic::new(format!(
+						"Source range overlaps the one on line {line}"
+					))
+					.at(number, column)
+					.with_hint(format!(
+						"Ranges of `{header}` must be disjoint"
+					)),
+				),
+				_ => {}
+			}
+			if reach.is_none_or(|(furthest, _)| end > furthest) {
+				reach = Some((end, number));
 			}
 		}
-		lowest
 	}
+
+	report
 }
 
 fn main() -> Result<()> {
-	let almanac = Almanac::from_file("inputs/day_05.txt");
+	let path = "inputs/day_05.txt";
+	if check::requested() {
+		check(&inputs::locate(path)?, &inputs::read_to_string(path)?).exit();
+	}
+
+	let almanac = Almanac::from_file(path)?;
 
 	let part_one = almanac.lowest_location_from_seeds();
 	let part_two = almanac.lowest_location_from_seeds_range();
@@ -220,7 +240,7 @@ mod tests {
 
 	#[test]
 	fn seed_79() {
-		let almanac = Almanac::from_file("inputs/test.txt");
+		let almanac = Almanac::from_file("inputs/test.txt").unwrap();
 		assert_eq!(almanac.seed_to_soil.get(79), 81);
 		assert_eq!(almanac.soil_to_fertilizer.get(81), 81);
 		assert_eq!(almanac.ferilizer_to_water.get(81), 81);
@@ -232,7 +252,7 @@ mod tests {
 
 	#[test]
 	fn seed_14() {
-		let almanac = Almanac::from_file("inputs/test.txt");
+		let almanac = Almanac::from_file("inputs/test.txt").unwrap();
 		assert_eq!(almanac.seed_to_soil.get(14), 14);
 		assert_eq!(almanac.soil_to_fertilizer.get(14), 53);
 		assert_eq!(almanac.ferilizer_to_water.get(53), 49);
@@ -244,7 +264,7 @@ mod tests {
 
 	#[test]
 	fn seed_55() {
-		let almanac = Almanac::from_file("inputs/test.txt");
+		let almanac = Almanac::from_file("inputs/test.txt").unwrap();
 		assert_eq!(almanac.seed_to_soil.get(55), 57);
 		assert_eq!(almanac.soil_to_fertilizer.get(57), 57);
 		assert_eq!(almanac.ferilizer_to_water.get(57), 53);
@@ -256,7 +276,7 @@ mod tests {
 
 	#[test]
 	fn seed_13() {
-		let almanac = Almanac::from_file("inputs/test.txt");
+		let almanac = Almanac::from_file("inputs/test.txt").unwrap();
 		assert_eq!(almanac.seed_to_soil.get(13), 13);
 		assert_eq!(almanac.soil_to_fertilizer.get(13), 52);
 		assert_eq!(almanac.ferilizer_to_water.get(52), 41);
@@ -265,4 +285,65 @@ mod tests {
 		assert_eq!(almanac.temperature_to_humidity.get(34), 35);
 		assert_eq!(almanac.humidity_to_location.get(35), 35);
 	}
+
+	#[test]
+	fn lowest_locations() {
+		let almanac = Almanac::from_file("inputs/test.txt").unwrap();
+		assert_eq!(almanac.lowest_location_from_seeds(), 35);
+		assert_eq!(almanac.lowest_location_from_seeds_range(), 46);
+
+		for seed in 0..120 {
+			let chained = (0..MAP_TYPES.len())
+				.fold(seed, |value, index| almanac[index].get(value));
+			assert_eq!(almanac.location_from_seed(seed), chained);
+		}
+	}
+
+	#[test]
+	fn skips_malformed_map_lines() {
+		parse::set_strict(false);
+		let almanac = Almanac::parse(
+			"seeds: 79 1\n\nseed-to-soil map:\n52 50 48\n50 98\n12x 5 1\n",
+		)
+		.unwrap();
+		assert_eq!(almanac.seed_to_soil.get(79), 81);
+		assert_eq!(almanac.seed_to_soil.get(98), 98);
+		assert_eq!(almanac.seed_to_soil.get(5), 5);
+	}
+
+	#[test]
+	fn check_input() {
+		let input = inputs::read_to_string("inputs/test.txt").unwrap();
+		assert!(check("inputs/test.txt", &input).is_clean());
+
+		let messages = |input| -> Vec<_> {
+			check("in.txt", input)
+				.violations()
+				.iter()
+				.map(|violation| {
+					(violation.message.clone(), violation.position)
+				})
+				.collect()
+		};
+		let overlapping =
+			"seeds: 1 2\n\nseed-to-soil map:\n50 98 2\n52 50 48\n0 99 5\n";
+		assert_eq!(
+			messages(overlapping),
+			[(
+				"Source range overlaps the one on line 4".to_string(),
+				Some((6, 3))
+			)]
+		);
+
+		let odd = "seeds: 1 2 3\n\nseed-to-soil map:\n50 98 2\n";
+		assert_eq!(
+			messages(odd),
+			[(
+				"Odd number of seeds, which come in `start length` pairs"
+					.to_string(),
+				Some((1, 1))
+			)]
+		);
+		assert!(Almanac::parse(odd).is_err());
+	}
 }
@@ -1,80 +1,136 @@
 use anyhow::{Error, Result};
-use std::{
-	fs::File,
-	io::{BufRead, BufReader},
+use aoc::{
+	check::{self, Report},
+	diagnostic::{Diagnose, Diagnostic},
+	inputs,
+	math::count_between_roots,
+	parse::{self, ParseError},
 };
 
-fn parse_file(path: &str) -> Result<(Vec<usize>, Vec<usize>)> {
-	let file = File::open(path)?;
-	let reader = BufReader::new(file);
-
-	let (time, distance) = reader.lines().try_fold(
-		(Vec::new(), Vec::new()),
-		|(mut times, mut distances), line| {
-			let line = line?;
-			let line = line.trim();
-
-			if line.starts_with("Time:") {
-				times = line
-					.split_whitespace()
-					.skip(1)
-					.filter_map(|s| s.parse().ok())
-					.collect();
-			} else if line.starts_with("Distance:") {
-				distances = line
-					.split_whitespace()
-					.skip(1)
-					.filter_map(|s| s.parse().ok())
-					.collect();
-			}
+type Races = (Vec<usize>, Vec<usize>);
+
+/// The numbers on a labeled line, with its line number.
+type Numbers = (usize, Vec<usize>);
 
-			Ok::<_, Error>((times, distances))
-		},
-	)?;
+fn parse_file(path: &str) -> Result<Races> {
+	let input = inputs::read_to_string(path)?;
+	Ok(parse(&input).diagnose(path, &input)?)
+}
+
+fn parse(input: &str) -> parse::Result<Races> {
+	let ((_, times), (_, distances)) = labeled_lines(input)?;
+	Ok((times, distances))
+}
+
+/// The `Time:` and `Distance:` lines.
+fn labeled_lines(input: &str) -> parse::Result<(Numbers, Numbers)> {
+	let mut lines = parse::lines(input);
+
+	let mut next = |label| match lines.next() {
+		Some((number, line)) => parse::labeled_integers(line, label)
+			.map(|numbers| (number, numbers))
+			.map_err(|error| error.on_line(number)),
+		None => Err(ParseError::new(1, 1, format!("Missing `{label}` line"))),
+	};
+
+	Ok((next("Time")?, next("Distance")?))
+}
 
-	Ok((time, distance))
+/// Each race needs both a time and a record distance.
+fn check(path: &str, input: &str) -> Report {
+	let mut report = Report::new(path, input);
+	if let Some(((_, times), (line, distances))) =
+		report.parsed(labeled_lines(input))
+	{
+		if times.len() != distances.len() {
+			report.add(
+				Diagnostic::new(format!(
+					"{} times but {} distances",
+					times.len(),
+					distances.len()
+				))
+				.at(line, 1)
+				.with_hint("Every race needs a time and a distance"),
+			);
+		}
+	}
+	report
 }
 
 fn main() -> Result<()> {
+	if check::requested() {
+		let path = "inputs/day_06.txt";
+		let input = inputs::read_to_string(path)?;
+		check(&inputs::locate(path)?, &input).exit();
+	}
+
 	let (times, distances) = parse_file("inputs/day_06.txt")?;
 
-	part_one(&times, &distances);
-	part_two(&times, &distances)?;
+	println!("Part 1: {}", part_one(&times, &distances)?);
+	println!("Part 2: {}", part_two(&times, &distances)?);
 
 	Ok(())
 }
 
-fn part_one(times: &[usize], distances: &[usize]) {
-	let margin_of_error =
-		times
-			.iter()
-			.zip(distances)
-			.fold(1, |acc, (&time, &distance)| {
-				acc * quadratic_formula(time as f64, distance as f64) as usize
-			});
-
-	println!("Part 1: {}", margin_of_error);
+fn part_one(times: &[usize], distances: &[usize]) -> Result<u128> {
+	times
+		.iter()
+		.zip(distances)
+		.try_fold(1, |acc, (&time, &distance)| {
+			Ok(acc * ways_to_win(time as u128, distance as u128)?)
+		})
 }
 
-fn part_two(times: &[usize], distances: &[usize]) -> Result<()> {
-	let time: usize = times.iter().copied().try_fold(0, |acc, x| {
-		Ok::<_, Error>(acc * 10usize.pow((x as f64).log10().ceil() as u32) + x)
-	})?;
-
-	let distance: usize = distances.iter().copied().try_fold(0, |acc, x| {
-		Ok::<_, Error>(acc * 10usize.pow((x as f64).log10().ceil() as u32) + x)
-	})?;
-
-	let margin_of_error = quadratic_formula(time as f64, distance as f64);
+fn part_two(times: &[usize], distances: &[usize]) -> Result<u128> {
+	ways_to_win(concatenate(times)?, concatenate(distances)?)
+}
 
-	println!("Part 2: {}", margin_of_error);
+/// Joins the digits of every number, as if the spaces weren't there.
+fn concatenate(numbers: &[usize]) -> Result<u128> {
+	numbers.iter().try_fold(0u128, |acc, &x| {
+		let digits = x.checked_ilog10().unwrap_or(0) + 1;
+		acc.checked_mul(10u128.pow(digits))
+			.and_then(|acc| acc.checked_add(x as u128))
+			.ok_or_else(|| Error::msg("Concatenated number is too large"))
+	})
+}
 
-	Ok(())
+/// Hold times `t` with `t * (time - t) > distance`, i.e. the integers
+/// strictly between the roots of `t^2 - time*t + distance`.
+fn ways_to_win(time: u128, distance: u128) -> Result<u128> {
+	Ok(count_between_roots(
+		-i128::try_from(time)?,
+		i128::try_from(distance)?,
+	)?)
 }
 
-fn quadratic_formula(hold_duration: f64, distance: f64) -> f64 {
-	let discriminant = (hold_duration * hold_duration - 4.0 * distance).sqrt();
-	let low = (hold_duration - discriminant) / 2.0;
-	let high = (hold_duration + discriminant) / 2.0;
-	high.ceil() - low.floor() - 1.
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn brute_force(time: u128, distance: u128) -> u128 {
+		(0..=time).filter(|t| t * (time - t) > distance).count() as u128
+	}
+
+	#[test]
+	fn matches_brute_force() {
+		for time in 0..60 {
+			for distance in 0..(time * time / 4 + 3) {
+				assert_eq!(
+					ways_to_win(time, distance).unwrap(),
+					brute_force(time, distance),
+					"{time} {distance}"
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn example() {
+		let (times, distances) = ([7, 15, 30], [9, 40, 200]);
+		assert_eq!(part_one(&times, &distances).unwrap(), 288);
+		assert_eq!(concatenate(&times).unwrap(), 71530);
+		assert_eq!(concatenate(&[10, 0, 100]).unwrap(), 100100);
+		assert_eq!(part_two(&times, &distances).unwrap(), 71503);
+	}
 }
@@ -1,9 +1,12 @@
 use anyhow::Result;
-use itertools::{Itertools, Position};
-use std::{
-	fs::{self},
-	ops::Deref,
+use aoc::{
+	check::{self, Report},
+	diagnostic::{Diagnose, Diagnostic},
+	inputs,
+	parse::{self, ParseError},
 };
+use itertools::{Itertools, Position};
+use std::ops::Deref;
 
 #[derive(Debug, Clone, Copy)]
 enum HandType {
@@ -16,10 +19,44 @@ enum HandType {
 	FiveOfAKind,
 }
 
+/// The values of a hand's five cards.
+type Cards = (u32, u32, u32, u32, u32);
+
+/// The hand's type and the values of its cards in order. Fails on unknown
+/// cards or a hand without five of them.
 fn score_hand(
 	hand: &str,
 	with_joker: bool,
-) -> (HandType, (u32, u32, u32, u32, u32)) {
+) -> parse::Result<(HandType, Cards)> {
+	let card_scores: Vec<u32> = hand
+		.char_indices()
+		.map(|(index, card)| match card {
+			'A' => Ok(14),
+			'K' => Ok(13),
+			'Q' => Ok(12),
+			'J' => Ok(if with_joker { 1 } else { 11 }),
+			'T' => Ok(10),
+			'2'..='9' => Ok(card as u32 - '0' as u32),
+			_ => Err(ParseError::new(
+				1,
+				index + 1,
+				format!("Unknown card `{card}`"),
+			)),
+		})
+		.collect::<parse::Result<_>>()?;
+	let wrong_size = || {
+		ParseError::new(
+			1,
+			1,
+			format!("Hand has {} cards, expected 5", card_scores.len()),
+		)
+	};
+	let card_scores = card_scores
+		.iter()
+		.copied()
+		.collect_tuple()
+		.ok_or_else(wrong_size)?;
+
 	let counts = hand.chars().counts();
 	let values = if with_joker {
 		counts.get(&'J').map_or_else(
@@ -57,57 +94,143 @@ fn score_hand(
 		"122" => HandType::TwoPair,
 		"1112" => HandType::OnePair,
 		"11111" => HandType::HighCard,
-		value => {
-			panic!("Could not parse hand to hand type: `{}`", value)
-		}
+		_ => return Err(wrong_size()),
 	};
 
-	let card_scores = hand
-		.chars()
-		.map(|card| match card {
-			'A' => 14,
-			'K' => 13,
-			'Q' => 12,
-			'J' => {
-				if with_joker {
-					1
-				} else {
-					11
-				}
-			}
-			'T' => 10,
-			value => value.to_digit(10).unwrap(),
-		})
-		.collect_tuple()
-		.unwrap();
+	Ok((hand_type, card_scores))
+}
+
+/// The hand and bid on a line like `32T3K 765`.
+fn hand_and_bid(line: &str) -> parse::Result<(&str, u32)> {
+	let (hand, bid) = line
+		.split_once(' ')
+		.ok_or_else(|| ParseError::new(1, 1, "Expected a hand and a bid"))?;
+	let bid = bid.parse().map_err(|error| {
+		ParseError::new(
+			1,
+			hand.len() + 2,
+			format!("Invalid bid `{bid}`: {error}"),
+		)
+	})?;
 
-	(hand_type, card_scores)
+	Ok((hand, bid))
 }
 
-fn process(input: &str, with_joker: bool) -> u32 {
-	input
-		.lines()
-		.filter(|x| !x.is_empty())
-		.map(|line| {
-			let (hand, bid) =
-				line.split_once(' ').expect("Invalid input format");
-			(
-				hand,
-				bid.parse::<u32>().unwrap(),
-				score_hand(hand, with_joker),
-			)
-		})
-		.sorted_by_key(|x| (x.2 .0 as u8, x.2 .1))
+fn process(input: &str, with_joker: bool) -> parse::Result<u64> {
+	let hands = parse::each_line(input, |line| {
+		if line.is_empty() {
+			return Ok(None);
+		}
+		let (hand, bid) = hand_and_bid(line)?;
+		Ok(Some((bid, score_hand(hand, with_joker)?)))
+	})?;
+
+	Ok(hands
+		.into_iter()
+		.flatten()
+		.sorted_by_key(|(_, (hand_type, cards))| (*hand_type as u8, *cards))
 		.enumerate()
-		.map(|(index, (_hand, bid, _))| (index as u32 + 1) * bid)
-		.sum::<u32>()
+		.map(|(index, (bid, _))| (index as u64 + 1) * u64::from(bid))
+		.sum())
+}
+
+const CARDS: &str = "AKQJT98765432";
+
+/// Every line must be a hand of five known cards followed by a bid.
+fn check(path: &str, input: &str) -> Report {
+	let mut report = Report::new(path, input);
+
+	for (number, line) in parse::lines(input).filter(|(_, x)| !x.is_empty()) {
+		let Some((hand, bid)) = line.split_once(' ') else {
+			report.add(
+				Diagnostic::new("Expected a hand and a bid")
+					.at(number, 1)
+					.underline(line.len())
+					.with_hint("Lines look like `32T3K 765`"),
+			);
+			continue;
+		};
+
+		let cards = hand.chars().count();
+		if cards != 5 {
+			report.add(
+				Diagnostic::new(format!("Hand has {cards} cards, expected 5"))
+					.at(number, 1)
+					.underline(hand.len()),
+			);
+		}
+		if let Some((index, card)) =
+			hand.char_indices().find(|&(_, card)| !CARDS.contains(card))
+		{
+			report.add(
+				Diagnostic::new(format!("Unknown card `{card}`"))
+					.at(number, index + 1)
+					.underline(1)
+					.with_hint(format!("Cards are one of `{CARDS}`")),
+			);
+		}
+		if let Err(error) = bid.parse::<u32>() {
+			report.error(
+				number,
+				hand.len() + 2,
+				format!("Invalid bid `{bid}`: {error}"),
+			);
+		}
+	}
+
+	report
 }
 
 fn main() -> Result<()> {
-	let input = fs::read_to_string("inputs/day_07.txt")?;
+	let path = "inputs/day_07.txt";
+	let input = inputs::read_to_string(path)?;
+	if check::requested() {
+		check(&inputs::locate(path)?, &input).exit();
+	}
 
-	println!("Part 1: {}", process(&input, false));
-	println!("Part 2: {}", process(&input, true));
+	println!("Part 1: {}", process(&input, false).diagnose(path, &input)?);
+	println!("Part 2: {}", process(&input, true).diagnose(path, &input)?);
 
 	Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn check_input() {
+		let example = "32T3K 765\nT55J5 684\nKK677 28\n";
+		assert!(check("in.txt", example).is_clean());
+		assert_eq!(process(example, false), Ok(765 + 684 * 3 + 28 * 2));
+
+		let report = check("in.txt", "32T3 765\nT55X5 684\nKK677\nKK677 x\n");
+		let positions: Vec<_> = report
+			.violations()
+			.iter()
+			.map(|violation| violation.position)
+			.collect();
+		assert_eq!(
+			positions,
+			[Some((1, 1)), Some((2, 4)), Some((3, 1)), Some((4, 7))]
+		);
+	}
+
+	#[test]
+	fn invalid_hands() {
+		let error = |input| process(input, true).unwrap_err();
+		assert_eq!(
+			error("32T3K 765\nT55X5 684\n").to_string(),
+			"line 2, column 4: Unknown card `X`"
+		);
+		assert_eq!(
+			(error("32T3 765\n").line, error("32T3 765\n").column),
+			(1, 1)
+		);
+		assert_eq!(error("KK677\n").message, "Expected a hand and a bid");
+		assert_eq!(
+			(error("KK677 x\n").line, error("KK677 x\n").column),
+			(1, 7)
+		);
+	}
+}
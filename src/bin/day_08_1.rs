@@ -1,35 +1,38 @@
 use anyhow::{anyhow, Result};
-use std::collections::BTreeMap;
-use std::fs::{self};
-
-type Graph = BTreeMap<String, (String, String)>;
+use aoc::{
+	animation::{Frame, Player},
+	check::{self, Report},
+	cli,
+	diagnostic::Diagnostic,
+	graph::Graph,
+	inputs, network,
+	render::Rgb,
+};
 
 fn traverse(
-	graph: &mut Graph,
+	graph: &Graph<char>,
 	directions: &[char],
 	start: &str,
 	end: &str,
 ) -> Result<()> {
-	let mut current = start.to_owned();
-	let destination = end.to_owned();
+	let mut current = graph
+		.id(start)
+		.ok_or_else(|| anyhow!("Invalid node: {start}"))?;
+	let destination = graph
+		.id(end)
+		.ok_or_else(|| anyhow!("Invalid node: {end}"))?;
 
 	let mut cycle_iter = directions.iter().cycle();
 
 	let mut steps = 0;
 	while current != destination {
-		let (left, right) = graph
-			.get(&current)
-			.ok_or_else(|| anyhow!("Invalid node: {current}"))?;
-
 		let direction = cycle_iter
 			.next()
 			.ok_or_else(|| anyhow!("Direction not found"))?;
 
-		match direction {
-			'L' => current = left.to_owned(),
-			'R' => current = right.to_owned(),
-			_ => return Err(anyhow!("Invalid direction: {direction}")),
-		}
+		current = graph
+			.edge(current, direction)
+			.ok_or_else(|| anyhow!("Invalid direction: {direction}"))?;
 
 		steps += 1;
 	}
@@ -39,40 +42,122 @@ fn traverse(
 	Ok(())
 }
 
-fn main() -> Result<()> {
-	let input = fs::read_to_string("inputs/day_08.txt")?;
-
-	let mut graph = BTreeMap::new();
-
-	let (directions, network) = input
-		.trim()
-		.split_once('\n')
-		.expect("There should be a newline");
-
-	let directions: Vec<char> = directions.chars().collect();
-
-	let network: Vec<_> = network.lines().filter(|x| !x.is_empty()).collect();
-
-	for element in network {
-		if let Some((node, left_right)) = element.split_once(" =") {
-			let trimmed = left_right
-				.trim_matches(|c: char| {
-					c == '(' || c == ')' || c.is_whitespace()
-				})
-				.replace(' ', "");
-
-			if let Some((left, right)) = trimmed.split_once(',') {
-				graph.insert(
-					node.to_owned(),
-					(left.to_owned(), right.to_owned()),
-				);
-			} else {
-				return Err(anyhow!("Invalid (LEFT,RIGHT) format"));
-			}
-		} else {
-			return Err(anyhow!("Invalid input format"));
+/// One frame per step: the current node, its two exits with the one taken
+/// marked, and how far through the directions the walk is.
+fn frames<'a>(
+	graph: &'a Graph<char>,
+	directions: &'a [char],
+	start: &'a str,
+	end: &'a str,
+) -> impl Iterator<Item = Frame> + 'a {
+	let mut current = graph.id(start);
+	let mut cycle_iter = directions.iter().enumerate().cycle();
+	let mut steps = 0;
+
+	std::iter::from_fn(move || {
+		let node = current?;
+		let name = graph.name(node);
+
+		if name == end {
+			current = None;
+			return Some(Frame::text(
+				[format!("  {name}")],
+				format!("Reached {end} in {steps} steps"),
+			));
+		}
+
+		let (index, direction) = cycle_iter.next()?;
+
+		let lines = std::iter::once(format!("  {name}")).chain(
+			graph.neighbors(node).map(|(label, to)| {
+				let marker = if label == direction { '>' } else { ' ' };
+				format!("{marker} {label} {}", graph.name(to))
+			}),
+		);
+
+		let frame = Frame::text(
+			lines,
+			format!(
+				"Step {steps}, direction {} of {}",
+				index + 1,
+				directions.len()
+			),
+		);
+
+		steps += 1;
+		current = graph.edge(node, direction);
+
+		Some(frame)
+	})
+}
+
+/// The walk needs both ends of the network.
+fn check(path: &str, input: &str) -> Report {
+	let mut report = Report::new(path, input);
+	let (_, elements) = network::check(&mut report, input);
+
+	for end in ["AAA", "ZZZ"] {
+		if !elements.iter().any(|(_, element)| element.node == end) {
+			report.add(Diagnostic::new(format!("Node `{end}` is missing")));
 		}
 	}
 
-	traverse(&mut graph, &directions, "AAA", "ZZZ")
+	report
+}
+
+fn main() -> Result<()> {
+	let path = "inputs/day_08.txt";
+	let input = inputs::read_to_string(path)?;
+	if check::requested() {
+		check(&inputs::locate(path)?, &input).exit();
+	}
+
+	let (directions, graph) = network::build(&input).map_err(|error| {
+		Diagnostic::from(error)
+			.in_file(path)
+			.with_source(input.as_ref())
+			.with_hint("Network lines look like `AAA = (BBB, CCC)`")
+	})?;
+
+	if cli::args().next().as_deref() == Some("animate") {
+		return Player::new(frames(&graph, &directions, "AAA", "ZZZ"))
+			.fps(20.0)
+			.palette(|c| (c == '>').then_some(Rgb::YELLOW))
+			.play();
+	}
+
+	traverse(&graph, &directions, "AAA", "ZZZ")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn check_input() {
+		let network =
+			"LLR\n\nAAA = (BBB, BBB)\nBBB = (AAA, ZZZ)\nZZZ = (ZZZ, ZZZ)\n";
+		assert!(check("in.txt", network).is_clean());
+
+		let broken = "LRX\n\nAAA = (BBB, CCC)\nAAA = (AAA, AAA)\nBBB = AAA\n";
+		let violations: Vec<_> = check("in.txt", broken)
+			.violations()
+			.iter()
+			.map(|violation| (violation.message.clone(), violation.position))
+			.collect();
+		assert_eq!(
+			violations,
+			[
+				("Unknown direction `X`".to_string(), Some((1, 3))),
+				("Expected ` = (`".to_string(), Some((5, 10))),
+				(
+					"Node `AAA` is already defined on line 3".to_string(),
+					Some((4, 1))
+				),
+				("Node `BBB` is never defined".to_string(), Some((3, 8))),
+				("Node `CCC` is never defined".to_string(), Some((3, 13))),
+				("Node `ZZZ` is missing".to_string(), None),
+			]
+		);
+	}
 }
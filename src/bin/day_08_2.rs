@@ -1,102 +1,227 @@
-use anyhow::{anyhow, Result};
-use std::collections::BTreeMap;
-use std::fs;
+use anyhow::{anyhow, bail, Result};
+use aoc::{
+	answer::Answer,
+	check::{self, Report},
+	cycle::{self, Cycle},
+	diagnostic::Diagnostic,
+	graph::{Graph, NodeId},
+	inputs,
+	math::{crt, MathError},
+	network,
+};
+use itertools::Itertools;
+
+/// A ghost's position: the node it is on and how far through the
+/// directions it is.
+type State = (NodeId, usize);
+
+fn traverse(graph: &Graph<char>, directions: &[char]) -> Result<Answer> {
+	if directions.is_empty() {
+		bail!("Missing directions");
+	}
+	// Nodes that are only led to have no edges of their own.
+	if let Some(node) = graph.nodes().find(|&node| {
+		directions
+			.iter()
+			.any(|direction| graph.edge(node, direction).is_none())
+	}) {
+		bail!("Node `{}` is never defined", graph.name(node));
+	}
 
-type Graph = BTreeMap<String, (String, String)>;
+	let is_end: Vec<bool> = graph
+		.nodes()
+		.map(|node| graph.name(node).ends_with('Z'))
+		.collect();
 
-fn traverse(graph: &Graph, directions: &[char]) -> Result<usize> {
-	let starting_nodes: Vec<_> =
-		graph.keys().filter(|x| x.ends_with('A')).collect();
+	let next = |&(node, index): &State| -> State {
+		let next = graph
+			.edge(node, &directions[index])
+			.expect("Checked every node has both edges");
+		(next, (index + 1) % directions.len())
+	};
+	let at_end = |&(node, _): &State| is_end[node as usize];
+
+	let ghosts: Vec<State> = graph
+		.nodes()
+		.filter(|&node| graph.name(node).ends_with('A'))
+		.map(|node| (node, 0))
+		.collect();
+	if ghosts.is_empty() {
+		bail!("No starting nodes");
+	}
 
-	let results: Vec<_> = starting_nodes
+	let cycles: Vec<Cycle> = ghosts
 		.iter()
-		.map(|node| {
-			let mut visited = vec![*node];
-			let mut current = *node;
+		.map(|&ghost| cycle::brent(ghost, next, at_end))
+		.collect();
+
+	// Until every ghost has reached its cycle, walk them together.
+	let tail = cycles.iter().map(|cycle| cycle.tail).max().unwrap_or(0);
+	let mut states = ghosts;
+	for step in 1..tail {
+		states = states.iter().map(next).collect();
+		if states.iter().all(at_end) {
+			return Ok(Answer::from(step));
+		}
+	}
+
+	// Usually each ghost ends its cycle on its only end node, so they meet
+	// at the lcm of the cycle lengths, which can outgrow the CRT's i128.
+	if cycles.iter().all(|cycle| cycle.matches == [cycle.length]) {
+		return cycles.iter().try_fold(Answer::from(1u64), |acc, cycle| {
+			Ok(acc.checked_lcm(&Answer::from(cycle.length))?)
+		});
+	}
+
+	// From then on a ghost is on an end node exactly on the steps congruent
+	// to one of its matches, so try every combination of matches.
+	let lower = tail.max(1) as i128;
+	let mut best: Option<i128> = None;
 
-			let steps = directions
+	for congruences in cycles
+		.iter()
+		.map(|cycle| {
+			cycle
+				.matches
 				.iter()
-				.cycle()
-				.enumerate()
-				.find_map(|(index, direction)| {
-					let (left, right) = graph.get(current)?;
-
-					let next = match direction {
-						'L' => left,
-						'R' => right,
-						_ => panic!("Invalid direction"),
-					};
-
-					if next.ends_with('Z') {
-						Some(index + 1)
-					} else {
-						visited.push(next);
-						current = next;
-						None
-					}
-				})
-				.expect("Should find a cycle");
-
-			Some(steps)
+				.map(|&step| (step as i128, cycle.length as i128))
 		})
-		.collect();
+		.multi_cartesian_product()
+	{
+		let (remainder, modulus) = match crt(&congruences) {
+			Ok(solution) => solution,
+			Err(MathError::NoSolution) => continue,
+			Err(error) => return Err(error.into()),
+		};
+
+		let laps = ((lower - remainder).max(0) + modulus - 1) / modulus;
+		let step = remainder + laps * modulus;
+		best = Some(best.map_or(step, |best| best.min(step)));
+	}
 
-	Ok(lcm(&results))
-}
+	let steps =
+		best.ok_or_else(|| anyhow!("Ghosts never reach end nodes together"))?;
 
-fn gcd(a: usize, b: usize) -> usize {
-	if b == 0 {
-		a
-	} else {
-		gcd(b, a % b)
-	}
+	Ok(Answer::from(u128::try_from(steps)?))
 }
 
-fn lcm(numbers: &[Option<usize>]) -> usize {
-	numbers
+/// The solver is quickest when every ghost first reaches an end node after
+/// exactly one cycle, as the puzzle's inputs are built to.
+fn check(path: &str, input: &str) -> Report {
+	let mut report = Report::new(path, input);
+	let (directions, elements) = network::check(&mut report, input);
+
+	let starts: Vec<_> = elements
 		.iter()
-		.cloned()
-		.fold(1, |acc, x| acc * x.unwrap_or(1) / gcd(acc, x.unwrap_or(1)))
+		.filter(|(_, element)| element.node.ends_with('A'))
+		.collect();
+	if starts.is_empty() {
+		report.add(
+			Diagnostic::new("No starting nodes")
+				.with_hint("Ghosts start on the nodes ending in `A`"),
+		);
+	}
+	if !report.is_clean() {
+		return report;
+	}
+
+	let mut graph = Graph::new();
+	for (_, element) in &elements {
+		graph.add_edge(element.node, 'L', element.left);
+		graph.add_edge(element.node, 'R', element.right);
+	}
+	let next = |&(node, index): &State| -> State {
+		let next = graph
+			.edge(node, &directions[index])
+			.expect("Checked network");
+		(next, (index + 1) % directions.len())
+	};
+	let at_end = |&(node, _): &State| graph.name(node).ends_with('Z');
+
+	for (number, element) in starts {
+		let start = (graph.id(element.node).expect("Added node"), 0);
+		let cycle = cycle::brent(start, next, at_end);
+		let first =
+			std::iter::successors(Some(start), |state| Some(next(state)))
+				.take(cycle.tail + cycle.length)
+				.position(|state| at_end(&state));
+
+		let problem = match first {
+			None => "never reaches an end node".to_string(),
+			Some(first) if first != cycle.length => format!(
+				"first reaches an end node after {first} steps, but loops \
+				 every {} steps",
+				cycle.length
+			),
+			Some(_) => continue,
+		};
+		report.add(
+			Diagnostic::new(format!("Ghost from `{}` {problem}", element.node))
+				.at(*number, 1)
+				.underline(element.node.len())
+				.with_hint(
+					"Each ghost should first reach an end node after exactly \
+					 one cycle",
+				),
+		);
+	}
+
+	report
 }
 
 fn main() -> Result<()> {
-	let input = fs::read_to_string("inputs/day_08.txt")?;
-
-	let mut graph = BTreeMap::new();
-
-	let (directions, network) = input
-		.trim()
-		.split_once('\n')
-		.ok_or_else(|| anyhow!("Invalid input format"))?;
-
-	let directions: Vec<char> = directions.chars().collect();
-
-	let network: Vec<_> = network.lines().filter(|x| !x.is_empty()).collect();
-
-	for element in network {
-		if let Some((node, left_right)) = element.split_once(" =") {
-			let trimmed = left_right
-				.trim_matches(|c: char| {
-					c == '(' || c == ')' || c.is_whitespace()
-				})
-				.replace(' ', "");
-
-			if let Some((left, right)) = trimmed.split_once(',') {
-				graph.insert(
-					node.to_owned(),
-					(left.to_owned(), right.to_owned()),
-				);
-			} else {
-				return Err(anyhow!("Invalid (LEFT,RIGHT) format"));
-			}
-		} else {
-			return Err(anyhow!("Invalid input format"));
-		}
+	let path = "inputs/day_08.txt";
+	let input = inputs::read_to_string(path)?;
+	if check::requested() {
+		check(&inputs::locate(path)?, &input).exit();
 	}
 
+	let (directions, graph) = network::build(&input).map_err(|error| {
+		Diagnostic::from(error)
+			.in_file(path)
+			.with_source(input.as_ref())
+			.with_hint("Network lines look like `AAA = (BBB, CCC)`")
+	})?;
+
 	let min_cycle = traverse(&graph, &directions)?;
 
 	println!("{}", min_cycle);
 
 	Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn invalid_networks() {
+		let (directions, graph) =
+			network::build("LR\n\nBBB = (BBB, BBB)\n").unwrap();
+		let error = traverse(&graph, &directions).unwrap_err();
+		assert_eq!(error.to_string(), "No starting nodes");
+
+		let (directions, graph) =
+			network::build("LR\n\n11A = (11B, 11Z)\n").unwrap();
+		let error = traverse(&graph, &directions).unwrap_err();
+		assert_eq!(error.to_string(), "Node `11B` is never defined");
+	}
+
+	#[test]
+	fn check_input() {
+		let network = "LR\n\n11A = (11B, XXX)\n11B = (XXX, 11Z)\n\
+		               11Z = (11B, XXX)\nXXX = (XXX, XXX)\n";
+		assert!(check("in.txt", network).is_clean());
+
+		// 22A reaches 22Z after 3 steps, then loops between 22Z and 22C.
+		let offset = "LR\n\n22A = (22B, 22B)\n22B = (22C, 22C)\n\
+		              22Z = (22C, 22C)\n22C = (22Z, 22Z)\n";
+		let report = check("in.txt", offset);
+		assert_eq!(report.violations().len(), 1);
+		assert_eq!(
+			report.violations()[0].message,
+			"Ghost from `22A` first reaches an end node after 3 steps, but \
+			 loops every 2 steps"
+		);
+	}
+}
@@ -1,66 +1,84 @@
-fn part_one(input: &str) -> i64 {
-	input
-		.split_terminator('\n')
-		.map(|line| {
-			let mut diffs = line
-				.split(' ')
-				.map(|s| s.parse::<i64>().unwrap())
-				.collect::<Vec<_>>();
+use anyhow::Result;
+use aoc::{
+	check::{self, Report},
+	diagnostic::Diagnostic,
+	inputs,
+	parse::{self, ParseError},
+	poly::Newton,
+	stream::Input,
+};
 
-			let mut numbers = Vec::new();
+/// The values after and before the sequence on a line.
+fn extrapolate(line: &str) -> parse::Result<(i128, i128)> {
+	let values = parse::integers::<i64>(line)?;
+	let invalid =
+		|error| ParseError::new(1, 1, format!("Can't extrapolate: {error}"));
+	let newton = Newton::fit(&values).map_err(invalid)?;
 
-			while diffs.iter().any(|x| *x != 0) {
-				diffs = diffs
-					.windows(2)
-					.enumerate()
-					.map(|(index, window)| {
-						match index {
-							last if last == diffs.len() - 2 => {
-								numbers.push(window[1]);
-							}
-							_ => {}
-						}
-						window[1] - window[0]
-					})
-					.collect();
+	Ok((
+		newton.forward(1).map_err(invalid)?,
+		newton.backward(1).map_err(invalid)?,
+	))
+}
+
+/// Every line must be a sequence that some polynomial fits.
+fn check(path: &str, input: &str) -> Report {
+	let mut report = Report::new(path, input);
+	for (number, line) in parse::lines(input) {
+		let values =
+			parse::integers::<i64>(line).map_err(|e| e.on_line(number));
+		if let Some(values) = report.parsed(values) {
+			if let Err(error) = Newton::fit(&values) {
+				report.add(
+					Diagnostic::new(format!("Can't extrapolate: {error}"))
+						.at(number, 1)
+						.underline(line.len()),
+				);
 			}
-			numbers.iter().sum::<i64>()
-		})
-		.sum()
+		}
+	}
+	report
 }
 
-fn part_two(input: &str) -> i64 {
-	input
-		.split_terminator('\n')
-		.map(|line| {
-			let mut diffs = line
-				.split(' ')
-				.map(|s| s.parse::<i64>().unwrap())
-				.collect::<Vec<_>>();
+fn main() -> Result<()> {
+	if check::requested() {
+		let path = "inputs/day_09.txt";
+		let input = inputs::read_to_string(path)?;
+		check(&inputs::locate(path)?, &input).exit();
+	}
 
-			let mut numbers = Vec::new();
+	let (next, previous) = sums(Input::from_args("inputs/day_09.txt")?)?;
 
-			while diffs.iter().any(|x| *x != 0) {
-				diffs = diffs
-					.windows(2)
-					.enumerate()
-					.map(|(index, window)| {
-						if index == 0 {
-							numbers.push(window[0]);
-						}
-						window[1] - window[0]
-					})
-					.collect();
-			}
-			numbers.iter().rev().fold(0, |acc, x| x - acc)
-		})
-		.sum()
+	println!("Part 1: {next}");
+	println!("Part 2: {previous}");
+
+	Ok(())
+}
+
+/// Sums of the values after and before every sequence.
+fn sums(input: Input) -> Result<(i128, i128)> {
+	let (mut next, mut previous): (i128, i128) = (0, 0);
+
+	input.each_line(|line| {
+		let (after, before) = extrapolate(line)?;
+		let overflow = || ParseError::new(1, 1, "Sum overflows");
+		next = next.checked_add(after).ok_or_else(overflow)?;
+		previous = previous.checked_add(before).ok_or_else(overflow)?;
+		Ok(())
+	})?;
+
+	Ok((next, previous))
 }
 
-fn main() {
-	let input = std::fs::read_to_string("inputs/day_09.txt")
-		.expect("Input file should exist");
+#[cfg(test)]
+mod tests {
+	use super::*;
 
-	println!("Part 1: {}", part_one(&input));
-	println!("Part 2: {}", part_two(&input));
+	#[test]
+	fn large_values() {
+		let lines = "4294967295 4294967295\n4294967295 4294967295\n";
+		let input = Input::new("sequences", lines.as_bytes());
+		let max = i128::from(u32::MAX);
+		assert_eq!(sums(input).unwrap(), (2 * max, 2 * max));
+	}
 }
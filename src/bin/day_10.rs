@@ -1,4 +1,15 @@
 use anyhow::{anyhow, Result};
+use aoc::{
+	animation::{Frame, Player},
+	check::{self, Report},
+	cli,
+	diagnostic::Diagnostic,
+	grid::Grid,
+	inputs,
+	render::{self, Rgb, Svg},
+};
+use itertools::Itertools;
+use std::fs;
 
 #[derive(Debug, Clone, Copy)]
 enum Direction {
@@ -8,109 +19,84 @@ enum Direction {
 	West,
 }
 
-fn part_one(bytes: &[u8]) -> Result<usize> {
-	let width = bytes
-		.iter()
-		.position(|&b| b == b'\n')
-		.ok_or(anyhow!("Invalid map: newline not found"))?;
+const PATH: &str = "inputs/day_10.txt";
 
-	let start = bytes
-		.iter()
-		.position(|&b| b == b'S')
-		.ok_or(anyhow!("Invalid map: starting position not found"))?;
+/// A diagnostic pointing at a byte of the map.
+fn invalid(bytes: &[u8], offset: usize, message: &str) -> Diagnostic {
+	let source = String::from_utf8_lossy(bytes);
+	Diagnostic::new(message)
+		.in_file(PATH)
+		.at_offset(&source, offset)
+		.underline(1)
+		.with_source(source)
+}
 
-	let (mut position, mut direction) = {
-		if matches!(bytes[start - width - 1], b'|' | b'7' | b'F') {
-			(start - width - 1, Direction::North)
-		} else if matches!(bytes[start + width + 1], b'|' | b'L' | b'J') {
-			(start + width + 1, Direction::South)
-		} else {
-			(start - 1, Direction::West)
-		}
-	};
+fn part_one(bytes: &[u8]) -> Result<usize> {
+	Ok(trace(bytes)?.len() / 2)
+}
 
-	Ok((1 + std::iter::repeat(())
-		.position(|_| {
-			if let Some(pipe) = bytes.get(position) {
-				match (pipe, direction) {
-					(b'|', Direction::South) => position += width + 1,
-					(b'|', Direction::North) => position -= width + 1,
-					(b'-', Direction::West) => position -= 1,
-					(b'-', Direction::East) => position += 1,
-					(b'L', Direction::South) | (b'F', Direction::North) => {
-						position += 1;
-						direction = Direction::East;
-					}
-					(b'L', Direction::West) | (b'J', Direction::East) => {
-						position -= width + 1;
-						direction = Direction::North;
-					}
-					(b'7', Direction::North) | (b'J', Direction::South) => {
-						position -= 1;
-						direction = Direction::West;
-					}
-					(b'7', Direction::East) | (b'F', Direction::West) => {
-						position += width + 1;
-						direction = Direction::South;
-					}
-					(b'S', _) => return true,
-					(_, _) => unreachable!(),
-				}
-				false
-			} else {
-				true
-			}
-		})
-		.ok_or(anyhow!("Invalid map: end condition not reached"))?)
-		/ 2)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tile {
+	Loop,
+	Inside,
+	Outside,
 }
 
-fn part_two(bytes: &[u8]) -> Result<usize> {
-	let width = bytes
-		.iter()
-		.position(|&b| b == b'\n')
-		.ok_or(anyhow!("Invalid map: newline not found"))?;
+/// Byte offsets of the loop's pipes, in the order they are walked.
+fn trace(bytes: &[u8]) -> Result<Vec<usize>> {
+	let width = bytes.iter().position(|&b| b == b'\n').ok_or_else(|| {
+		Diagnostic::new("Newline not found")
+			.in_file(PATH)
+			.with_hint("The map needs at least one full row")
+	})?;
 
-	let start = bytes
-		.iter()
-		.position(|&b| b == b'S')
-		.ok_or(anyhow!("Invalid map: starting position not found"))?;
+	let start = bytes.iter().position(|&b| b == b'S').ok_or_else(|| {
+		Diagnostic::new("Starting position not found")
+			.in_file(PATH)
+			.with_hint("The map needs one `S` tile")
+	})?;
 
-	let mut pipes = vec![false; bytes.len()];
+	let mut pipes = Vec::new();
 
+	// Moves off the top or left edge wrap around to offsets past the end,
+	// so they leave the map like moves off the bottom.
+	let north = start.wrapping_sub(width + 1);
+	let south = start + width + 1;
 	let (mut position, mut direction) = {
-		if matches!(bytes[start - width - 1], b'|' | b'7' | b'F') {
-			(start - width - 1, Direction::North)
-		} else if matches!(bytes[start + width + 1], b'|' | b'L' | b'J') {
-			(start + width + 1, Direction::South)
+		if matches!(bytes.get(north), Some(b'|' | b'7' | b'F')) {
+			(north, Direction::North)
+		} else if matches!(bytes.get(south), Some(b'|' | b'L' | b'J')) {
+			(south, Direction::South)
 		} else {
-			(start - 1, Direction::West)
+			(start.wrapping_sub(1), Direction::West)
 		}
 	};
 
+	let mut broken = None;
+
 	std::iter::repeat(())
 		.position(|_| {
-			if let Some(pipe) = pipes.get_mut(position) {
-				*pipe = true;
-			} else {
-				panic!("Index out of bounds");
-			}
 			if let Some(pipe) = bytes.get(position) {
+				pipes.push(position);
 				match (pipe, direction) {
 					(b'|', Direction::South) => position += width + 1,
-					(b'|', Direction::North) => position -= width + 1,
-					(b'-', Direction::West) => position -= 1,
+					(b'|', Direction::North) => {
+						position = position.wrapping_sub(width + 1)
+					}
+					(b'-', Direction::West) => {
+						position = position.wrapping_sub(1)
+					}
 					(b'-', Direction::East) => position += 1,
 					(b'L', Direction::South) | (b'F', Direction::North) => {
 						position += 1;
 						direction = Direction::East;
 					}
 					(b'L', Direction::West) | (b'J', Direction::East) => {
-						position -= width + 1;
+						position = position.wrapping_sub(width + 1);
 						direction = Direction::North;
 					}
 					(b'7', Direction::North) | (b'J', Direction::South) => {
-						position -= 1;
+						position = position.wrapping_sub(1);
 						direction = Direction::West;
 					}
 					(b'7', Direction::East) | (b'F', Direction::West) => {
@@ -118,34 +104,224 @@ fn part_two(bytes: &[u8]) -> Result<usize> {
 						direction = Direction::South;
 					}
 					(b'S', _) => return true,
-					(_, _) => unreachable!(),
+					(_, _) => {
+						broken = Some(position);
+						return true;
+					}
 				}
 				false
 			} else {
+				broken = Some(pipes.last().copied().unwrap_or(start));
 				true
 			}
 		})
 		.ok_or(anyhow!("Invalid map: end condition not reached"))?;
 
+	if let Some(position) = broken {
+		return Err(invalid(bytes, position, "The loop is broken here")
+			.with_hint(
+				"The loop must be closed, with every pipe joining the next",
+			)
+			.into());
+	}
+
+	Ok(pipes)
+}
+
+fn classify(bytes: &[u8]) -> Result<Grid<Tile>> {
+	let grid = Grid::from_bytes(bytes)?;
+	let width = grid.width();
+
+	let mut pipes = vec![false; bytes.len()];
+	for position in trace(bytes)? {
+		pipes[position] = true;
+	}
+
 	let mut inside = false;
 
-	Ok(bytes
+	let tiles = bytes
 		.iter()
 		.enumerate()
-		.filter(|(position, tile)| {
-			let is_pipe = *pipes.get(*position).unwrap_or(&false);
+		.filter_map(|(position, tile)| {
+			let is_pipe = pipes[position];
 			inside &= position % (width + 1) != 0;
 			inside ^= is_pipe && matches!(*tile, b'|' | b'F' | b'7');
-			inside
-				&& (!is_pipe || **tile == b'.')
-				&& (position % (width + 1) != width)
+
+			(position % (width + 1) != width).then_some(
+				match (is_pipe, inside) {
+					(true, _) => Tile::Loop,
+					(false, true) => Tile::Inside,
+					(false, false) => Tile::Outside,
+				},
+			)
 		})
+		.collect();
+
+	Ok(Grid::from_cells(width, grid.height(), tiles))
+}
+
+fn part_two(bytes: &[u8]) -> Result<usize> {
+	Ok(classify(bytes)?
+		.cells()
+		.iter()
+		.filter(|&&tile| tile == Tile::Inside)
 		.count())
 }
 
+/// Draws the loop, inside and outside tiles to `path`. SVG output also
+/// traces the loop through the tile centers.
+fn render(bytes: &[u8], path: &str) -> Result<()> {
+	let tiles = classify(bytes)?;
+
+	let color = |tile: &Tile| match tile {
+		Tile::Loop => Rgb::YELLOW,
+		Tile::Inside => Rgb::GREEN,
+		Tile::Outside => Rgb::BLACK,
+	};
+
+	if path.ends_with(".svg") {
+		let stride = tiles.width() + 1;
+		let mut points: Vec<_> = trace(bytes)?
+			.into_iter()
+			.map(|position| (position % stride, position / stride))
+			.collect();
+		points.extend(points.first().copied());
+
+		let svg = Svg::new(&tiles, 8, color)
+			.with_path(points, Rgb::RED)
+			.render();
+		fs::write(path, svg)?;
+	} else {
+		render::save(path, &tiles, 4, color)?;
+	}
+
+	Ok(())
+}
+
+fn box_drawing(pipe: char) -> char {
+	match pipe {
+		'|' => '│',
+		'-' => '─',
+		'L' => '└',
+		'J' => '┘',
+		'7' => '┐',
+		'F' => '┌',
+		c => c,
+	}
+}
+
+/// Walks the loop in the terminal, revealing one row's worth of pipes per
+/// frame.
+fn animate(bytes: &[u8]) -> Result<()> {
+	let mut cells = Grid::from_bytes(bytes)?.map(|&b| b as char);
+	let stride = cells.width() + 1;
+	let pipes = trace(bytes)?;
+	let per_frame = cells.width().max(1);
+
+	let frames = pipes.chunks(per_frame).enumerate().map(|(index, chunk)| {
+		for &position in chunk {
+			let position = (position % stride, position / stride);
+			cells[position] = box_drawing(cells[position]);
+		}
+
+		let walked = index * per_frame + chunk.len();
+		Frame::new(
+			cells.clone(),
+			format!("{walked} of {} pipes walked", pipes.len()),
+		)
+	});
+
+	Player::new(frames)
+		.fps(30.0)
+		.palette(|c| match c {
+			'S' => Some(Rgb::RED),
+			'│' | '─' | '└' | '┘' | '┐' | '┌' => Some(Rgb::YELLOW),
+			_ => Some(Rgb::GREY),
+		})
+		.play()
+}
+
+/// The map must have one `S`, rows of equal width and a closed loop.
+fn check(path: &str, bytes: &[u8]) -> Report {
+	let source = String::from_utf8_lossy(bytes);
+	let mut report = Report::new(path, &source);
+	report.rectangular();
+	report.alphabet("|-LJ7F.S");
+
+	let starts: Vec<usize> = bytes.iter().positions(|&b| b == b'S').collect();
+	for &extra in starts.iter().skip(1) {
+		report.add(
+			Diagnostic::new("Second starting position")
+				.at_offset(&source, extra)
+				.underline(1)
+				.with_hint("The map needs exactly one `S` tile"),
+		);
+	}
+
+	if report.is_clean() {
+		if let Err(error) = trace(bytes) {
+			report.add(match error.downcast::<Diagnostic>() {
+				Ok(diagnostic) => diagnostic,
+				Err(error) => Diagnostic::new(error.to_string()),
+			});
+		}
+	}
+
+	report
+}
+
 fn main() -> Result<()> {
-	let bytes = include_bytes!("../../inputs/day_10.txt");
+	let bytes = &*inputs::read(PATH)?;
+	if check::requested() {
+		check(&inputs::locate(PATH)?, bytes).exit();
+	}
+
 	println!("Part 1: {}", part_one(bytes)?);
 	println!("Part 2: {}", part_two(bytes)?);
+
+	let mut args = cli::args();
+	match args.next().as_deref() {
+		Some("render") => {
+			let path = args.next().ok_or(anyhow!("Missing output path"))?;
+			render(bytes, &path)?;
+		}
+		Some("animate") => animate(bytes)?,
+		_ => {}
+	}
+
 	Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn violations(map: &str) -> Vec<String> {
+		check("in.txt", map.as_bytes())
+			.violations()
+			.iter()
+			.map(|violation| violation.message.clone())
+			.collect()
+	}
+
+	#[test]
+	fn closed_loop() {
+		let map = ".....\n.S-7.\n.|.|.\n.L-J.\n.....\n";
+		assert!(violations(map).is_empty());
+		assert_eq!(part_one(map.as_bytes()).unwrap(), 4);
+	}
+
+	#[test]
+	fn rejects_broken_maps() {
+		assert_eq!(
+			violations(".....\n.S-7.\n.|.|.\n.L-..\n"),
+			["The loop is broken here"]
+		);
+		assert_eq!(violations("S-7\n|.|\nL-S\n"), ["Second starting position"]);
+		assert_eq!(
+			violations("S-7\n|.|\nL-\n"),
+			["Row has width 2, expected 3"]
+		);
+		assert_eq!(violations("S7\n.|\n"), ["The loop is broken here"]);
+	}
+}
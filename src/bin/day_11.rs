@@ -1,49 +1,78 @@
-fn part_one(bytes: &[u8]) -> usize {
-	let size = bytes.iter().position(|&c| c == b'\n').unwrap();
-	let (x, y) = count_occurrences(bytes, size);
-	dist(&x, 1) + dist(&y, 1)
-}
+use aoc::{
+	answer::Answer,
+	check::{self, Report},
+	compress::Compression,
+	distance::manhattan_sum,
+	inputs, math,
+	sparse::SparseGrid,
+};
 
-fn part_two(bytes: &[u8]) -> usize {
-	let size = bytes.iter().position(|&c| c == b'\n').unwrap();
-	let (x, y) = count_occurrences(bytes, size);
-	dist(&x, 999_999) + dist(&y, 999_999)
+fn part_one(bytes: &[u8]) -> math::Result<Answer> {
+	scaled_distances(bytes, 2)
 }
 
-fn count_occurrences(bytes: &[u8], size: usize) -> (Vec<usize>, Vec<usize>) {
-	let mut x = vec![0; size];
-	let mut y = vec![0; size];
+fn part_two(bytes: &[u8]) -> math::Result<Answer> {
+	scaled_distances(bytes, 1_000_000)
+}
 
-	for (pos, _) in bytes.iter().enumerate().filter(|&(_, &b)| b == b'#') {
-		x[pos % (size + 1)] += 1;
-		y[pos / (size + 1)] += 1;
-	}
+/// The distance sum grows by the same amount for every extra unit of
+/// expansion, so any factor follows from factors 1 and 2 without expanding
+/// coordinates that far.
+fn scaled_distances(bytes: &[u8], factor: u128) -> math::Result<Answer> {
+	let unexpanded = expanded_distances(bytes, 1)?;
+	let per_unit = expanded_distances(bytes, 2)? - unexpanded;
 
-	(x, y)
+	Answer::from(factor.saturating_sub(1))
+		.checked_mul(&Answer::from(per_unit))?
+		.checked_add(&Answer::from(unexpanded))
 }
 
-fn dist(counts: &[usize], inc: usize) -> usize {
-	counts
+fn expanded_distances(bytes: &[u8], factor: i64) -> math::Result<u128> {
+	let galaxies: Vec<[i64; 2]> =
+		SparseGrid::from_bytes(bytes, |b| (b == b'#').then_some(()))
+			.points()
+			.map(|(x, y)| [x, y])
+			.collect();
+
+	let [columns, rows] = [0, 1].map(|axis| {
+		Compression::with_gap_weight(
+			galaxies.iter().map(|galaxy| galaxy[axis]),
+			|missing| missing.checked_mul(factor),
+		)
+	});
+	let axes = [columns?, rows?];
+
+	let expanded: Vec<[i64; 2]> = galaxies
 		.iter()
-		.enumerate()
-		.fold((0, 0, 0, 0), |(gaps, sum, items, dist), (i, &count)| {
-			if count > 0 {
-				let expanded = i + inc * gaps;
-				(
-					gaps,
-					sum + count * expanded,
-					items + count,
-					dist + count * (items * expanded - sum),
-				)
-			} else {
-				(gaps + 1, sum, items, dist)
-			}
+		.map(|galaxy| {
+			[0, 1].map(|axis| {
+				axes[axis]
+					.expand(galaxy[axis])
+					.expect("Galaxy coordinate should be compressed")
+			})
 		})
-		.3
+		.collect();
+
+	Ok(manhattan_sum(&expanded, [1, 1]))
 }
 
-pub fn main() {
-	let bytes = include_bytes!("../../inputs/day_11.txt");
-	println!("Part 1: {}", part_one(bytes));
-	println!("Part 2: {}", part_two(bytes));
+/// The image must be a rectangle of empty space and galaxies.
+fn check(path: &str, input: &str) -> Report {
+	let mut report = Report::new(path, input);
+	report.rectangular();
+	report.alphabet(".#");
+	report
+}
+
+pub fn main() -> anyhow::Result<()> {
+	let path = "inputs/day_11.txt";
+	let bytes = inputs::read(path)?;
+	if check::requested() {
+		check(&inputs::locate(path)?, &String::from_utf8_lossy(&bytes)).exit();
+	}
+
+	println!("Part 1: {}", part_one(&bytes)?);
+	println!("Part 2: {}", part_two(&bytes)?);
+
+	Ok(())
 }
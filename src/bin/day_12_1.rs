@@ -1,3 +1,10 @@
+use aoc::{
+	check::{self, Report},
+	diagnostic::Diagnostic,
+	inputs,
+	parse::{self, ParseError},
+	stream::Input,
+};
 use itertools::{repeat_n, Itertools};
 
 #[derive(Debug)]
@@ -45,35 +52,86 @@ impl Record<'_> {
 	}
 }
 
-fn part_one(input: &str) {
-	let sum: usize = input
-		.lines()
-		.filter_map(|line| {
-			let mut parts = line.split_whitespace();
-			let springs = parts.next()?;
-			let groups = parts
-				.next()?
-				.split(',')
-				.filter_map(|s| s.parse().ok())
-				.collect::<Vec<usize>>();
-			let unknown =
-				springs.chars().counts().get(&'?').copied().unwrap_or(0) as u8;
-
-			Some(Record {
-				springs,
-				groups,
-				unknown,
-			})
-		})
-		.map(|record| record.possible_options())
-		.sum();
+/// The record on a line. Lines without groups, or whose groups don't
+/// parse, are skipped as `None` unless in strict mode, as is anything after
+/// the groups.
+fn record(line: &str) -> parse::Result<Option<Record<'_>>> {
+	let mut parts = line.split_whitespace();
+	let (Some(springs), Some(groups)) = (parts.next(), parts.next()) else {
+		if !line.trim().is_empty() {
+			parse::skip(ParseError::new(
+				1,
+				1,
+				"Expected springs and group sizes",
+			))?;
+		}
+		return Ok(None);
+	};
 
-	println!("Sum: {:#?}", sum);
+	let groups: Vec<usize> = match parse::comma_list(groups) {
+		Ok(groups) => groups,
+		Err(error) => {
+			let column = parse::column(line, groups) + error.column - 1;
+			parse::skip(ParseError::new(1, column, error.message))?;
+			return Ok(None);
+		}
+	};
+	if let Some(extra) = parts.next() {
+		parse::skip(ParseError::new(
+			1,
+			parse::column(line, extra),
+			format!("Unexpected `{extra}` after the group sizes"),
+		))?;
+	}
+	let unknown =
+		springs.chars().counts().get(&'?').copied().unwrap_or(0) as u8;
+
+	Ok(Some(Record {
+		springs,
+		groups,
+		unknown,
+	}))
+}
+
+/// Every line must be springs from `.#?` and a list of group sizes. Lines
+/// are read as the solver reads them, so both accept the same input.
+fn check(path: &str, input: &str) -> Report {
+	let mut report = Report::new(path, input);
+	for (number, line) in parse::lines(input) {
+		let record = record(line).map_err(|error| error.on_line(number));
+		let Some(Some(record)) = report.parsed(record) else {
+			continue;
+		};
+		let springs = record.springs;
+		if let Some(index) = springs.find(|c| !matches!(c, '.' | '#' | '?')) {
+			report.add(
+				Diagnostic::new("Unknown spring")
+					.at(number, index + 1)
+					.underline(1)
+					.with_hint("Springs are one of `.#?`"),
+			);
+		}
+	}
+	report
 }
 
-fn main() {
-	let input = std::fs::read_to_string("inputs/day_12.txt")
-		.expect("Input file should exist");
+fn main() -> anyhow::Result<()> {
+	let path = "inputs/day_12.txt";
+	if check::requested() {
+		let input = inputs::read_to_string(path)?;
+		check(&inputs::locate(path)?, &input).exit();
+	}
+
+	let mut sum = 0;
+
+	Input::from_args(path)?.each_line(|line| {
+		if let Some(record) = record(line)? {
+			sum += record.possible_options();
+		}
+		Ok(())
+	})?;
+
+	println!("Sum: {:#?}", sum);
 
-	part_one(&input);
+	Ok(())
 }
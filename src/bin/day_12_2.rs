@@ -1,83 +1,262 @@
-use itertools::{repeat_n, Itertools};
+use aoc::{
+	check::{self, Report},
+	diagnostic::Diagnostic,
+	inputs,
+	memo::{Dense, Memo},
+	parse::{self, ParseError},
+	stream::Input,
+};
 
+/// Position in the springs and number of groups already placed.
+type State = (usize, usize);
+
+/// A row of springs and its group sizes, unfolded `copies` times. The
+/// unfolded row is never built: both lists are indexed modulo the folded
+/// ones, with a `?` between copies of the springs.
 #[derive(Debug)]
-struct Record {
-	springs: Vec<char>,
-	unknown: u32,
-	groups: Vec<u32>,
+struct Record<'a> {
+	springs: &'a [u8],
+	groups: &'a [usize],
+	copies: usize,
 }
 
-impl Record {
-	fn get_options(&self) -> impl Iterator<Item = String> {
-		repeat_n([".", "#"].into_iter(), self.unknown as usize)
-			.multi_cartesian_product()
-			.map(|v| v.join(""))
+impl<'a> Record<'a> {
+	/// The record on a line, with its groups parsed into `groups`, a buffer
+	/// reused from line to line. Lines without groups, or whose groups don't
+	/// parse, are skipped as `None` unless in strict mode, as is anything
+	/// after the groups.
+	fn parse(
+		line: &'a str,
+		copies: usize,
+		groups: &'a mut Vec<usize>,
+	) -> parse::Result<Option<Self>> {
+		let mut parts = line.split_whitespace();
+		let (Some(springs), Some(text)) = (parts.next(), parts.next()) else {
+			if !line.trim().is_empty() {
+				parse::skip(ParseError::new(
+					1,
+					1,
+					"Expected springs and group sizes",
+				))?;
+			}
+			return Ok(None);
+		};
+
+		if let Err(error) = parse::comma_list_into(text, groups) {
+			let column = parse::column(line, text) + error.column - 1;
+			parse::skip(ParseError::new(1, column, error.message))?;
+			return Ok(None);
+		}
+		if let Some(extra) = parts.next() {
+			parse::skip(ParseError::new(
+				1,
+				parse::column(line, extra),
+				format!("Unexpected `{extra}` after the group sizes"),
+			))?;
+		}
+
+		Ok(Some(Record {
+			springs: springs.as_bytes(),
+			groups,
+			copies,
+		}))
 	}
 
-	fn check_option(&self, option: &str) -> bool {
-		let mut option_iter = option.chars();
-
-		let filled_options: String = self
-			.springs
-			.iter()
-			.map(|&c| match c {
-				'?' => option_iter.next().unwrap(),
-				_ => c,
-			})
-			.collect();
-
-		let counts = filled_options
-			.chars()
-			.group_by(|c| c == &'#')
-			.into_iter()
-			.filter_map(|(is_hashes, group)| {
-				is_hashes.then_some(group.into_iter().count() as u32)
-			})
-			.collect::<Vec<u32>>();
-
-		self.groups[..] == counts[..]
+	fn len(&self) -> usize {
+		(self.springs.len() + 1) * self.copies - 1
+	}
+
+	fn spring(&self, index: usize) -> Option<u8> {
+		if index >= self.len() {
+			return None;
+		}
+		let offset = index % (self.springs.len() + 1);
+		Some(self.springs.get(offset).copied().unwrap_or(b'?'))
+	}
+
+	fn group_count(&self) -> usize {
+		self.groups.len() * self.copies
+	}
+
+	fn group(&self, group: usize) -> Option<usize> {
+		(group < self.group_count())
+			.then(|| self.groups[group % self.groups.len()])
+	}
+
+	/// Arrangements of the springs from `index` onwards that match the
+	/// groups from `group` onwards.
+	fn arrangements(
+		&self,
+		memo: &mut Memo<State, usize, Dense<State, usize>>,
+		index: usize,
+		group: usize,
+	) -> usize {
+		memo.get_or_compute((index, group), |memo| {
+			let Some(spring) = self.spring(index) else {
+				return (group == self.group_count()) as usize;
+			};
+
+			let mut count = 0;
+
+			if spring != b'#' {
+				count += self.arrangements(memo, index + 1, group);
+			}
+
+			if spring != b'.' {
+				if let Some(size) = self.group(group) {
+					let end = index + size;
+					let fits = end <= self.len()
+						&& (index..end).all(|i| self.spring(i) != Some(b'.'))
+						&& self.spring(end) != Some(b'#');
+
+					if fits {
+						let next = (end + 1).min(self.len());
+						count += self.arrangements(memo, next, group + 1);
+					}
+				}
+			}
+
+			count
+		})
 	}
 
 	fn possible_options(&self) -> usize {
-		self.get_options()
-			.filter(|option| self.check_option(option))
-			.count()
+		let mut memo = Memo::with_cache(Dense::grid(
+			self.len() + 1,
+			self.group_count() + 1,
+		));
+		self.arrangements(&mut memo, 0, 0)
 	}
 }
 
-fn part_two(input: &str) {
-	let sum: usize = input
-		.lines()
-		.filter_map(|line| {
-			let mut parts = line.split_whitespace();
-			let springs = parts.next()?.chars().collect::<Vec<_>>();
-			let springs = std::iter::repeat(springs)
-				.take(5)
-				.flatten()
-				.collect::<Vec<_>>();
-
-			let groups = parts
-				.next()?
-				.split(',')
-				.filter_map(|s| s.parse().ok())
-				.collect::<Vec<u32>>();
-			let unknown = springs.iter().filter(|c| **c == '?').count() as u32;
-
-			Some(Record {
-				springs,
-				groups: std::iter::repeat(groups).take(5).flatten().collect(),
-				unknown,
-			})
-		})
-		.map(|record| record.possible_options())
-		.sum();
+fn total_options(input: Input, copies: usize) -> anyhow::Result<usize> {
+	let mut sum: usize = 0;
+	let mut groups = Vec::new();
+	input.each_line(|line| {
+		if let Some(record) = Record::parse(line, copies, &mut groups)? {
+			sum = sum
+				.checked_add(record.possible_options())
+				.ok_or_else(|| ParseError::new(1, 1, "Sum overflows"))?;
+		}
+		Ok(())
+	})?;
+
+	Ok(sum)
+}
+
+/// Every line must be springs from `.#?` and a list of group sizes. Lines
+/// are read as the solver reads them, so both accept the same input.
+fn check(path: &str, input: &str) -> Report {
+	let mut report = Report::new(path, input);
+	let mut groups = Vec::new();
+	for (number, line) in parse::lines(input) {
+		let record = Record::parse(line, 1, &mut groups)
+			.map_err(|error| error.on_line(number));
+		let Some(Some(record)) = report.parsed(record) else {
+			continue;
+		};
+		let springs = record.springs;
+		if let Some(index) = springs.iter().position(|c| !b".#?".contains(c)) {
+			report.add(
+				Diagnostic::new("Unknown spring")
+					.at(number, index + 1)
+					.underline(1)
+					.with_hint("Springs are one of `.#?`"),
+			);
+		}
+	}
+	report
+}
+
+fn main() -> anyhow::Result<()> {
+	let path = "inputs/day_12.txt";
+	if check::requested() {
+		let input = inputs::read_to_string(path)?;
+		check(&inputs::locate(path)?, &input).exit();
+	}
+
+	let sum = total_options(Input::from_args(path)?, 5)?;
 
 	println!("Sum: {:#?}", sum);
+
+	Ok(())
 }
 
-fn main() {
-	let input = std::fs::read_to_string("inputs/test.txt")
-		.expect("Input file should exist");
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use aoc::alloc::{self, Counting};
+
+	#[global_allocator]
+	static ALLOCATOR: Counting = Counting;
+
+	const EXAMPLE: &str = "???.### 1,1,3
+.??..??...?##. 1,1,3
+?#?#?#?#?#?#?#? 1,3,1,6
+????.#...#... 4,1,1
+????.######..#####. 1,6,5
+?###???????? 3,2,1";
+
+	#[test]
+	fn example() {
+		let example = || Input::new("example", EXAMPLE.as_bytes());
+		assert_eq!(total_options(example(), 1).unwrap(), 21);
+		assert_eq!(total_options(example(), 5).unwrap(), 525152);
+	}
+
+	#[test]
+	fn check_input() {
+		// Any whitespace between the parts, as the solver allows.
+		let spaced = "???.###  1,1,3\n.??..??...?##.\t1,1,3\n";
+		assert!(check("in.txt", spaced).is_clean());
+		assert_eq!(
+			total_options(Input::new("in.txt", spaced.as_bytes()), 1).unwrap(),
+			5
+		);
+
+		let report = check("in.txt", "?x? 1\n");
+		assert_eq!(report.violations().len(), 1);
+		assert_eq!(report.violations()[0].position, Some((1, 2)));
+	}
+
+	#[test]
+	fn errors_point_at_the_token() {
+		let groups = |line| {
+			Record::parse(line, 1, &mut Vec::new())
+				.map(|record| record.map(|record| record.groups.len()))
+		};
+		parse::set_strict(true);
+		let bad = groups("???.###  1,x,3 extra");
+		let extra = groups("???.### 1,1,3  extra");
+		parse::set_strict(false);
+		let skipped = groups("???.### 1,1,3  extra");
+
+		assert_eq!(bad.unwrap_err().column, 12);
+		let error = extra.unwrap_err();
+		assert_eq!(error.column, 16);
+		assert_eq!(error.message, "Unexpected `extra` after the group sizes");
+		assert_eq!(skipped, Ok(Some(3)));
+	}
+
+	#[test]
+	fn matches_part_one() {
+		let input = Input::open("inputs/day_12.txt").unwrap();
+		assert_eq!(total_options(input, 1).unwrap(), 8022);
+	}
 
-	part_two(&input);
+	#[test]
+	fn records_borrow_springs() {
+		let input = inputs::read_to_string("inputs/day_12.txt").unwrap();
+		let mut groups = Vec::new();
+		let (count, allocations) = alloc::measure(|| {
+			input
+				.lines()
+				.filter(|line| Record::parse(line, 5, &mut groups).is_ok())
+				.count()
+		});
+
+		assert_eq!(count, input.lines().count());
+		// Unfolding into owned rows took over 7000 allocations.
+		assert!(allocations.count < 10, "{allocations}");
+	}
 }
@@ -0,0 +1,191 @@
+use crate::{
+	cli,
+	diagnostic::Diagnostic,
+	parse::{self, ParseError},
+};
+
+/// The argument that makes a day binary validate its input instead of
+/// solving it.
+pub const COMMAND: &str = "check-input";
+
+/// Whether the binary was run as `day_NN check-input`.
+pub fn requested() -> bool {
+	cli::args().next().as_deref() == Some(COMMAND)
+}
+
+/// Everything wrong with an input: bad grammar, and broken assumptions the
+/// solver relies on. Checks keep going after a violation so a single run
+/// reports them all.
+#[derive(Debug, Clone)]
+pub struct Report {
+	file: String,
+	source: String,
+	violations: Vec<Diagnostic>,
+}
+
+impl Report {
+	pub fn new(file: &str, source: &str) -> Self {
+		Self {
+			file: file.to_string(),
+			source: source.to_string(),
+			violations: Vec::new(),
+		}
+	}
+
+	pub fn source(&self) -> &str {
+		&self.source
+	}
+
+	/// Lines of the input, numbered from 1.
+	pub fn lines(&self) -> impl Iterator<Item = (usize, &str)> {
+		parse::lines(&self.source)
+	}
+
+	/// Records a violation, pointing it into this input. Only the line it
+	/// points at is kept, not the whole input.
+	pub fn add(&mut self, diagnostic: Diagnostic) {
+		let line = diagnostic.position.and_then(|(line, _)| {
+			self.source.lines().nth(line.checked_sub(1)?)
+		});
+		let diagnostic = match line {
+			Some(line) => diagnostic.with_line(line),
+			None => diagnostic,
+		};
+		self.violations.push(diagnostic.in_file(self.file.as_str()));
+	}
+
+	pub fn error(
+		&mut self,
+		line: usize,
+		column: usize,
+		message: impl Into<String>,
+	) {
+		self.add(Diagnostic::new(message).at(line, column));
+	}
+
+	/// The parsed value, or `None` after recording the error.
+	pub fn parsed<T>(&mut self, result: parse::Result<T>) -> Option<T> {
+		match result {
+			Ok(value) => Some(value),
+			Err(error) => {
+				self.add(Diagnostic::from(error));
+				None
+			}
+		}
+	}
+
+	/// Checks that every line has the width of the first, returning it.
+	pub fn rectangular(&mut self) -> usize {
+		let mut width = None;
+		let mut ragged = Vec::new();
+
+		for (number, line) in self.lines() {
+			match width {
+				None => width = Some(line.len()),
+				Some(width) if width != line.len() => {
+					ragged.push(ParseError::new(
+						number,
+						width.min(line.len()) + 1,
+						format!(
+							"Row has width {}, expected {}",
+							line.len(),
+							width
+						),
+					));
+				}
+				_ => {}
+			}
+		}
+
+		for error in ragged {
+			self.add(Diagnostic::from(error).underline(1));
+		}
+
+		width.unwrap_or(0)
+	}
+
+	/// Checks that every character is one of `alphabet`, reporting the
+	/// first stray one on each line.
+	pub fn alphabet(&mut self, alphabet: &str) {
+		let stray: Vec<_> = self
+			.lines()
+			.filter_map(|(number, line)| {
+				let (index, c) = line
+					.char_indices()
+					.find(|&(_, c)| !alphabet.contains(c))?;
+				Some((number, index + 1, c))
+			})
+			.collect();
+
+		for (number, column, c) in stray {
+			self.add(
+				Diagnostic::new(format!("Unexpected character `{c}`"))
+					.at(number, column)
+					.underline(1)
+					.with_hint(format!("Only `{alphabet}` are allowed")),
+			);
+		}
+	}
+
+	pub fn violations(&self) -> &[Diagnostic] {
+		&self.violations
+	}
+
+	pub fn is_clean(&self) -> bool {
+		self.violations.is_empty()
+	}
+
+	/// Prints the violations to stderr, or a line saying the input is fine,
+	/// and exits with a matching status.
+	pub fn exit(self) -> ! {
+		if self.is_clean() {
+			println!("{}: ok", self.file);
+			std::process::exit(0);
+		}
+
+		for violation in &self.violations {
+			violation.emit();
+			eprintln!();
+		}
+		eprintln!(
+			"{}: {} violation{}",
+			self.file,
+			self.violations.len(),
+			if self.violations.len() == 1 { "" } else { "s" }
+		);
+		std::process::exit(1);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn collects_every_violation() {
+		let mut report = Report::new("grid.txt", "..#\n.#\n#..x\n");
+		assert_eq!(report.rectangular(), 3);
+		report.alphabet(".#");
+
+		let positions: Vec<_> = report
+			.violations()
+			.iter()
+			.map(|violation| violation.position)
+			.collect();
+		assert_eq!(positions, [Some((2, 3)), Some((3, 4)), Some((3, 4))]);
+		assert_eq!(report.violations()[0].file.as_deref(), Some("grid.txt"));
+		assert!(report.violations()[2].to_string().contains("3 | #..x\n"));
+	}
+
+	#[test]
+	fn parse_errors() {
+		let mut report = Report::new("in.txt", "1 2\n");
+		assert_eq!(report.parsed(Ok(3)), Some(3));
+		assert_eq!(
+			report.parsed::<()>(Err(ParseError::new(1, 2, "Bad"))),
+			None
+		);
+		assert!(!report.is_clean());
+		assert_eq!(report.violations()[0].position, Some((1, 2)));
+	}
+}
@@ -0,0 +1,24 @@
+/// The program's arguments after its name, without any `--flags`.
+pub fn args() -> impl Iterator<Item = String> {
+	std::env::args()
+		.skip(1)
+		.filter(|arg| !arg.starts_with("--"))
+}
+
+/// The program's `--flags`, for passing on to the programs it runs.
+pub fn flags() -> impl Iterator<Item = String> {
+	std::env::args().skip(1).filter(|arg| arg.starts_with("--"))
+}
+
+/// Whether the program was run with `--name`.
+pub fn flag(name: &str) -> bool {
+	flags().any(|flag| flag.strip_prefix("--") == Some(name))
+}
+
+/// The value of `--name=value`, if the program was run with it.
+pub fn option(name: &str) -> Option<String> {
+	flags().find_map(|flag| {
+		let value = flag.strip_prefix("--")?.strip_prefix(name)?;
+		Some(value.strip_prefix('=')?.to_string())
+	})
+}
@@ -0,0 +1,137 @@
+use crate::math::{MathError, Result};
+
+/// Maps a sparse set of coordinates onto dense indices `0..len()` and back.
+///
+/// Every coordinate also gets an expanded position: consecutive present
+/// coordinates are one unit apart, plus whatever weight the gap of missing
+/// coordinates between them is given. With the default weight a gap counts
+/// once per missing coordinate, so expanded positions equal the original
+/// coordinates.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Compression {
+	coords: Vec<i64>,
+	expanded: Vec<i64>,
+}
+
+impl Compression {
+	pub fn new(coords: impl IntoIterator<Item = i64>) -> Self {
+		let coords = sorted(coords);
+		Self {
+			expanded: coords.clone(),
+			coords,
+		}
+	}
+
+	/// Builds a compression where a run of `missing` absent coordinates
+	/// between two present ones spans `weight(missing)` expanded units, or
+	/// fails if a weight or position doesn't fit in an `i64`.
+	pub fn with_gap_weight(
+		coords: impl IntoIterator<Item = i64>,
+		weight: impl Fn(i64) -> Option<i64>,
+	) -> Result<Self> {
+		let coords = sorted(coords);
+		let mut expanded: Vec<i64> = Vec::with_capacity(coords.len());
+
+		for (index, &coord) in coords.iter().enumerate() {
+			let position = match index {
+				0 => Some(coord),
+				_ => {
+					let previous = coords[index - 1];
+					coord
+						.checked_sub(previous)
+						.and_then(|gap| weight(gap - 1))
+						.and_then(|gap| gap.checked_add(1))
+						.and_then(|gap| gap.checked_add(expanded[index - 1]))
+				}
+			};
+			expanded.push(position.ok_or(MathError::Overflow)?);
+		}
+
+		Ok(Self { coords, expanded })
+	}
+
+	pub fn len(&self) -> usize {
+		self.coords.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.coords.is_empty()
+	}
+
+	/// Dense index of `coord`, if it is one of the compressed coordinates.
+	pub fn index(&self, coord: i64) -> Option<usize> {
+		self.coords.binary_search(&coord).ok()
+	}
+
+	/// Dense index of the first compressed coordinate `>= coord`.
+	pub fn lower_bound(&self, coord: i64) -> usize {
+		self.coords.partition_point(|&c| c < coord)
+	}
+
+	pub fn coord(&self, index: usize) -> Option<i64> {
+		self.coords.get(index).copied()
+	}
+
+	pub fn expanded(&self, index: usize) -> Option<i64> {
+		self.expanded.get(index).copied()
+	}
+
+	/// Expanded position of `coord`, if it is one of the compressed
+	/// coordinates.
+	pub fn expand(&self, coord: i64) -> Option<i64> {
+		self.index(coord).map(|index| self.expanded[index])
+	}
+
+	pub fn coords(&self) -> &[i64] {
+		&self.coords
+	}
+}
+
+fn sorted(coords: impl IntoIterator<Item = i64>) -> Vec<i64> {
+	let mut coords: Vec<i64> = coords.into_iter().collect();
+	coords.sort_unstable();
+	coords.dedup();
+	coords
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn round_trip() {
+		let compression = Compression::new([100, -5, 7, 100, 3]);
+		assert_eq!(compression.coords(), &[-5, 3, 7, 100]);
+		assert_eq!(compression.index(7), Some(2));
+		assert_eq!(compression.index(8), None);
+		assert_eq!(compression.coord(3), Some(100));
+		assert_eq!(compression.lower_bound(8), 3);
+		assert_eq!(compression.expand(100), Some(100));
+	}
+
+	#[test]
+	fn weighted_gaps() {
+		let compression =
+			Compression::with_gap_weight([0, 1, 4, 5], |missing| {
+				missing.checked_mul(10)
+			})
+			.unwrap();
+		assert_eq!(compression.expand(1), Some(1));
+		assert_eq!(compression.expand(4), Some(22));
+		assert_eq!(compression.expand(5), Some(23));
+	}
+
+	#[test]
+	fn overflowing_gaps() {
+		let huge = |missing: i64| missing.checked_mul(1 << 40);
+		assert_eq!(
+			Compression::with_gap_weight([0, 1 << 30], huge),
+			Err(MathError::Overflow)
+		);
+		assert_eq!(
+			Compression::with_gap_weight([i64::MIN, i64::MAX], Some),
+			Err(MathError::Overflow)
+		);
+		assert_eq!(Compression::new([i64::MIN, i64::MAX]).len(), 2);
+	}
+}
@@ -0,0 +1,167 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// Shape of the sequence `start, f(start), f(f(start)), ...`: the first
+/// `tail` states never repeat, after which the sequence loops every `length`
+/// steps. `matches` holds the steps in `tail..tail + length` whose state
+/// satisfied the predicate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle {
+	pub tail: usize,
+	pub length: usize,
+	pub matches: Vec<usize>,
+}
+
+impl Cycle {
+	/// The step within the first pass of the sequence whose state equals the
+	/// state at `step`.
+	pub fn reduce(&self, step: usize) -> usize {
+		if step < self.tail {
+			step
+		} else {
+			self.tail + (step - self.tail) % self.length
+		}
+	}
+
+	/// Whether the state at `step` (at or after the tail) matches.
+	pub fn is_match(&self, step: usize) -> bool {
+		step >= self.tail
+			&& self.matches.binary_search(&self.reduce(step)).is_ok()
+	}
+
+	/// Every matching step from the start of the cycle onwards, in order.
+	pub fn matching_steps(&self) -> impl Iterator<Item = usize> + '_ {
+		(0..).flat_map(move |lap| {
+			self.matches
+				.iter()
+				.map(move |step| step + lap * self.length)
+		})
+	}
+}
+
+/// Brent's algorithm: finds the cycle in constant memory, then walks it once
+/// more to collect the matching steps.
+pub fn brent<S: Clone + Eq>(
+	start: S,
+	f: impl Fn(&S) -> S,
+	predicate: impl Fn(&S) -> bool,
+) -> Cycle {
+	let mut power = 1;
+	let mut length = 1;
+	let mut tortoise = start.clone();
+	let mut hare = f(&start);
+
+	while tortoise != hare {
+		if power == length {
+			tortoise = hare.clone();
+			power *= 2;
+			length = 0;
+		}
+		hare = f(&hare);
+		length += 1;
+	}
+
+	let mut tortoise = start.clone();
+	let mut hare = (0..length).fold(start, |state, _| f(&state));
+	let mut tail = 0;
+
+	while tortoise != hare {
+		tortoise = f(&tortoise);
+		hare = f(&hare);
+		tail += 1;
+	}
+
+	let mut state = tortoise;
+	let mut matches = Vec::new();
+	for step in tail..tail + length {
+		if predicate(&state) {
+			matches.push(step);
+		}
+		state = f(&state);
+	}
+
+	Cycle {
+		tail,
+		length,
+		matches,
+	}
+}
+
+/// Finds the cycle by remembering the step every state was first seen at.
+/// Uses memory for the whole first pass but calls `f` only once per step.
+pub fn hashed<S: Clone + Eq + Hash>(
+	start: S,
+	f: impl Fn(&S) -> S,
+	predicate: impl Fn(&S) -> bool,
+) -> Cycle {
+	let mut seen = HashMap::new();
+	let mut matched = Vec::new();
+	let mut state = start;
+
+	let tail = loop {
+		if let Some(&step) = seen.get(&state) {
+			break step;
+		}
+		matched.push(predicate(&state));
+		let next = f(&state);
+		seen.insert(state, seen.len());
+		state = next;
+	};
+
+	let length = seen.len() - tail;
+	let matches = (tail..seen.len()).filter(|&step| matched[step]).collect();
+
+	Cycle {
+		tail,
+		length,
+		matches,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// 0 -> 1 -> 2 -> 3 -> 4 -> 5 -> 6 -> 7 -> 3 -> ...
+	fn next(x: &u32) -> u32 {
+		if *x == 7 {
+			3
+		} else {
+			x + 1
+		}
+	}
+
+	#[test]
+	fn both_finders_agree() {
+		let even = |x: &u32| x.is_multiple_of(2);
+		let expected = Cycle {
+			tail: 3,
+			length: 5,
+			matches: vec![4, 6],
+		};
+
+		assert_eq!(brent(0, next, even), expected);
+		assert_eq!(hashed(0, next, even), expected);
+	}
+
+	#[test]
+	fn pure_cycle() {
+		let cycle = brent(0u32, |x| (x + 3) % 7, |&x| x == 0);
+		assert_eq!((cycle.tail, cycle.length), (0, 7));
+		assert_eq!(cycle.matches, vec![0]);
+	}
+
+	#[test]
+	fn billionth_step() {
+		let cycle = hashed(0, next, |&x| x == 5);
+		let step = 1_000_000_000;
+		let state = (0..cycle.reduce(step)).fold(0, |x, _| next(&x));
+
+		assert_eq!(state, [3, 4, 5, 6, 7][(step - 3) % 5]);
+		assert!(cycle.is_match(step + 5));
+		assert!(!cycle.is_match(step + 1));
+		assert_eq!(
+			cycle.matching_steps().take(3).collect::<Vec<_>>(),
+			vec![5, 10, 15]
+		);
+	}
+}
@@ -0,0 +1,314 @@
+use crate::parse::ParseError;
+use std::{
+	fmt::{self, Display, Write},
+	io::IsTerminal,
+};
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[1;31m";
+const BLUE: &str = "\x1b[1;34m";
+const CYAN: &str = "\x1b[1;36m";
+
+/// What a tab is shown as, so the underline lines up in any terminal.
+const TAB: &str = "    ";
+
+/// Terminal columns `text` takes up once tabs are expanded.
+fn display_width(text: &str) -> usize {
+	text.chars()
+		.map(|c| if c == '\t' { TAB.len() } else { 1 })
+		.sum()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+	Plain,
+	/// ANSI colors, for terminals.
+	Colored,
+}
+
+impl Style {
+	/// Colored when stderr is a terminal and `NO_COLOR` isn't set.
+	pub fn detect() -> Style {
+		match std::env::var_os("NO_COLOR").is_none()
+			&& std::io::stderr().is_terminal()
+		{
+			true => Style::Colored,
+			false => Style::Plain,
+		}
+	}
+
+	fn paint(self, color: &str, text: &str) -> String {
+		match self {
+			Style::Plain => text.to_string(),
+			Style::Colored => format!("{color}{text}{RESET}"),
+		}
+	}
+}
+
+/// A rejected input, rendered like a compiler error: the message, where it
+/// happened, the offending line with the bad span underlined, and a hint.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Diagnostic {
+	pub message: String,
+	pub file: Option<String>,
+	/// Line and column, both counting from 1, with the column in bytes.
+	pub position: Option<(usize, usize)>,
+	/// Characters to underline. Defaults to the word at the column.
+	pub width: Option<usize>,
+	pub hint: Option<String>,
+	source: Option<String>,
+	/// The offending line, when the whole source isn't kept.
+	snippet: Option<String>,
+}
+
+impl Diagnostic {
+	pub fn new(message: impl Into<String>) -> Self {
+		Self {
+			message: message.into(),
+			..Self::default()
+		}
+	}
+
+	pub fn in_file(mut self, file: impl Into<String>) -> Self {
+		self.file = Some(file.into());
+		self
+	}
+
+	pub fn at(mut self, line: usize, column: usize) -> Self {
+		self.position = Some((line, column));
+		self
+	}
+
+	/// Points at a byte offset into the source.
+	pub fn at_offset(self, source: &str, offset: usize) -> Self {
+		let before = &source[..offset.min(source.len())];
+		let line = before.matches('\n').count() + 1;
+		let column = offset - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+		self.at(line, column)
+	}
+
+	pub fn underline(mut self, width: usize) -> Self {
+		self.width = Some(width);
+		self
+	}
+
+	/// The whole input, so the offending line can be shown.
+	pub fn with_source(mut self, source: impl Into<String>) -> Self {
+		self.source = Some(source.into());
+		self
+	}
+
+	/// Just the offending line, for input that was streamed rather than
+	/// read whole.
+	pub fn with_line(mut self, line: impl Into<String>) -> Self {
+		self.snippet = Some(line.into());
+		self
+	}
+
+	pub fn with_hint(mut self, hint: impl Into<String>) -> Self {
+		self.hint = Some(hint.into());
+		self
+	}
+
+	pub fn render(&self, style: Style) -> String {
+		let mut out = format!(
+			"{}{}\n",
+			style.paint(RED, "error"),
+			style.paint(BOLD, &format!(": {}", self.message))
+		);
+		self.render_body(style, &mut out);
+		out
+	}
+
+	/// Prints the diagnostic to stderr, colored if it is a terminal.
+	pub fn emit(&self) {
+		eprint!("{}", self.render(Style::detect()));
+	}
+
+	/// Everything below the message line.
+	fn render_body(&self, style: Style, out: &mut String) {
+		let line_text = self.position.and_then(|(line, _)| {
+			self.snippet.as_deref().or_else(|| {
+				self.source.as_deref()?.lines().nth(line.checked_sub(1)?)
+			})
+		});
+		let gutter = match (self.position, line_text) {
+			(Some((line, _)), Some(_)) => line.to_string().len(),
+			_ => 1,
+		};
+		let pad = " ".repeat(gutter);
+		let bar = style.paint(BLUE, "|");
+
+		let location = match (&self.file, self.position) {
+			(Some(file), Some((line, column))) => {
+				Some(format!("{file}:{line}:{column}"))
+			}
+			(Some(file), None) => Some(file.clone()),
+			(None, Some((line, column))) => {
+				Some(format!("line {line}, column {column}"))
+			}
+			(None, None) => None,
+		};
+		if let Some(location) = location {
+			let _ =
+				writeln!(out, "{pad}{} {location}", style.paint(BLUE, "-->"));
+		}
+
+		if let (Some((line, column)), Some(text)) = (self.position, line_text) {
+			// The column counts bytes of the line as given, so measure on
+			// that before expanding tabs.
+			let mut offset = column.saturating_sub(1).min(text.len());
+			while !text.is_char_boundary(offset) {
+				offset -= 1;
+			}
+			let (before, after) = text.split_at(offset);
+			let start = display_width(before);
+			let word = after.chars().take_while(|c| !c.is_whitespace()).count();
+			let room = display_width(after);
+			let width = self.width.unwrap_or(word).min(room).max(1);
+			let text = text.replace('\t', TAB);
+
+			let number = style.paint(BLUE, &line.to_string());
+			let carets = style.paint(RED, &"^".repeat(width));
+			let _ = writeln!(out, "{pad} {bar}");
+			let _ = writeln!(out, "{number} {bar} {text}");
+			let _ = writeln!(out, "{pad} {bar} {}{carets}", " ".repeat(start));
+		}
+
+		if let Some(hint) = &self.hint {
+			let _ =
+				writeln!(out, "{pad} {} {hint}", style.paint(CYAN, "= hint:"));
+		}
+	}
+}
+
+/// Plain text without the leading `error:` label, so it reads well after
+/// the `Error: ` that `main` prints for a returned error.
+impl Display for Diagnostic {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let mut body = String::new();
+		self.render_body(Style::Plain, &mut body);
+		write!(f, "{}\n{}", self.message, body.trim_end())
+	}
+}
+
+impl std::error::Error for Diagnostic {}
+
+impl From<ParseError> for Diagnostic {
+	fn from(error: ParseError) -> Self {
+		Diagnostic::new(error.message).at(error.line, error.column)
+	}
+}
+
+/// Turns parse errors into diagnostics pointing into the input. Only used
+/// on the way out of a day, so the size of the error doesn't matter.
+#[allow(clippy::result_large_err)]
+pub trait Diagnose<T> {
+	fn diagnose(self, file: &str, source: &str) -> Result<T, Diagnostic>;
+}
+
+impl<T> Diagnose<T> for Result<T, ParseError> {
+	fn diagnose(self, file: &str, source: &str) -> Result<T, Diagnostic> {
+		self.map_err(|error| {
+			Diagnostic::from(error).in_file(file).with_source(source)
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const SOURCE: &str = "LR\n\nAAA = (BBB, BBB)\nBBB = AAA, ZZZ)\n";
+
+	#[test]
+	fn plain() {
+		let diagnostic = Diagnostic::new("Expected `(`")
+			.in_file("inputs/day_08.txt")
+			.at(4, 7)
+			.with_source(SOURCE)
+			.with_hint("Network lines look like `AAA = (BBB, CCC)`");
+
+		assert_eq!(
+			diagnostic.render(Style::Plain),
+			"error: Expected `(`
+ --> inputs/day_08.txt:4:7
+  |
+4 | BBB = AAA, ZZZ)
+  |       ^^^^
+  = hint: Network lines look like `AAA = (BBB, CCC)`
+"
+		);
+		assert!(diagnostic.to_string().starts_with("Expected `(`\n -->"));
+	}
+
+	#[test]
+	fn colored() {
+		let rendered = Diagnostic::new("Bad")
+			.at(1, 1)
+			.with_source("x")
+			.render(Style::Colored);
+		assert!(rendered.starts_with("\x1b[1;31merror\x1b[0m"));
+		assert!(rendered.contains("\x1b[1;31m^\x1b[0m"));
+	}
+
+	#[test]
+	fn positions() {
+		let diagnostic = Diagnostic::new("Bad").at_offset(SOURCE, 9);
+		assert_eq!(diagnostic.position, Some((3, 6)));
+
+		let error = ParseError::new(3, 12, "Invalid value");
+		let rendered = Err::<(), _>(error)
+			.diagnose("in.txt", SOURCE)
+			.unwrap_err()
+			.underline(10)
+			.render(Style::Plain);
+		assert!(rendered.contains(" --> in.txt:3:12\n"), "{rendered}");
+		assert!(rendered.contains("\n  |            ^^^^^\n"), "{rendered}");
+	}
+
+	#[test]
+	fn single_line() {
+		let rendered = Diagnostic::new("Invalid value")
+			.in_file("-")
+			.at(120, 6)
+			.underline(1)
+			.with_line("Game x: 3 blue")
+			.render(Style::Plain);
+		assert!(rendered.contains("\n120 | Game x: 3 blue\n"), "{rendered}");
+		assert!(rendered.contains("\n    |      ^\n"), "{rendered}");
+	}
+
+	#[test]
+	fn tabs_and_wide_characters() {
+		let rendered = Diagnostic::new("Bad")
+			.at(1, 3)
+			.with_line("\t\tx y")
+			.render(Style::Plain);
+		assert!(rendered.contains("\n1 |         x y\n"), "{rendered}");
+		assert!(rendered.contains("\n  |         ^\n"), "{rendered}");
+
+		// Column 2 is inside the `é`, so the caret falls back onto it.
+		let rendered = Diagnostic::new("Bad")
+			.at(1, 2)
+			.with_line("é\tz")
+			.render(Style::Plain);
+		assert!(rendered.contains("\n1 | é    z\n"), "{rendered}");
+		assert!(rendered.contains("\n  | ^\n"), "{rendered}");
+	}
+
+	#[test]
+	fn without_source() {
+		assert_eq!(
+			Diagnostic::new("Starting position not found")
+				.in_file("inputs/day_10.txt")
+				.with_hint("The map needs one `S` tile")
+				.render(Style::Plain),
+			"error: Starting position not found
+ --> inputs/day_10.txt
+  = hint: The map needs one `S` tile
+"
+		);
+	}
+}
@@ -0,0 +1,262 @@
+pub type Point<const N: usize> = [i64; N];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+	Manhattan,
+	Chebyshev,
+	EuclideanSquared,
+}
+
+impl Metric {
+	pub fn distance<const N: usize>(self, a: &Point<N>, b: &Point<N>) -> u128 {
+		match self {
+			Metric::Manhattan => manhattan(a, b),
+			Metric::Chebyshev => chebyshev(a, b),
+			Metric::EuclideanSquared => euclidean_squared(a, b),
+		}
+	}
+
+	/// Smallest distance two points can have when they are `delta` apart
+	/// along a single axis.
+	fn axis_bound(self, delta: u64) -> u128 {
+		let delta = delta as u128;
+		match self {
+			Metric::Manhattan | Metric::Chebyshev => delta,
+			Metric::EuclideanSquared => delta * delta,
+		}
+	}
+}
+
+/// Indices of two points and the distance between them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pair {
+	pub first: usize,
+	pub second: usize,
+	pub distance: u128,
+}
+
+pub fn manhattan<const N: usize>(a: &Point<N>, b: &Point<N>) -> u128 {
+	a.iter().zip(b).map(|(a, b)| a.abs_diff(*b) as u128).sum()
+}
+
+pub fn chebyshev<const N: usize>(a: &Point<N>, b: &Point<N>) -> u128 {
+	a.iter()
+		.zip(b)
+		.map(|(a, b)| a.abs_diff(*b) as u128)
+		.max()
+		.unwrap_or(0)
+}
+
+/// Saturates at `u128::MAX`, which takes differences of over 2^63 along
+/// more than one axis.
+pub fn euclidean_squared<const N: usize>(a: &Point<N>, b: &Point<N>) -> u128 {
+	a.iter()
+		.zip(b)
+		.map(|(a, b)| (a.abs_diff(*b) as u128).pow(2))
+		.fold(0, u128::saturating_add)
+}
+
+/// Sum of `|a - b|` over all unordered pairs of `values`, in O(n log n).
+pub fn pairwise_sum(values: impl IntoIterator<Item = i64>) -> u128 {
+	let mut values: Vec<i64> = values.into_iter().collect();
+	values.sort_unstable();
+
+	values
+		.iter()
+		.enumerate()
+		.fold((0i128, 0i128), |(prefix, sum), (index, &value)| {
+			let value = value as i128;
+			(prefix + value, sum + value * index as i128 - prefix)
+		})
+		.1 as u128
+}
+
+/// Sum of Manhattan distances over all unordered pairs of `points`, with the
+/// distance along each axis multiplied by that axis' weight.
+pub fn manhattan_sum<const N: usize>(
+	points: &[Point<N>],
+	weights: [u64; N],
+) -> u128 {
+	(0..N)
+		.map(|axis| {
+			weights[axis] as u128
+				* pairwise_sum(points.iter().map(|point| point[axis]))
+		})
+		.sum()
+}
+
+/// Closest pair of distinct points, found by sweeping along the first axis
+/// and skipping candidates that are already too far apart on it.
+pub fn nearest_pair<const N: usize>(
+	points: &[Point<N>],
+	metric: Metric,
+) -> Option<Pair> {
+	let mut order: Vec<usize> = (0..points.len()).collect();
+	order.sort_unstable_by_key(|&index| points[index].first().copied());
+
+	let mut best: Option<Pair> = None;
+
+	for (i, &first) in order.iter().enumerate() {
+		for &second in order[..i].iter().rev() {
+			let delta = match N {
+				0 => 0,
+				_ => points[first][0].abs_diff(points[second][0]),
+			};
+
+			if best
+				.is_some_and(|best| metric.axis_bound(delta) >= best.distance)
+			{
+				break;
+			}
+
+			let distance = metric.distance(&points[first], &points[second]);
+
+			if best.is_none_or(|best| distance < best.distance) {
+				best = Some(Pair {
+					first: first.min(second),
+					second: first.max(second),
+					distance,
+				});
+			}
+		}
+	}
+
+	best
+}
+
+/// Pair of points that are furthest apart. Manhattan and Chebyshev take
+/// O(n * 2^N) and O(n * N) respectively, Euclidean compares every pair.
+pub fn farthest_pair<const N: usize>(
+	points: &[Point<N>],
+	metric: Metric,
+) -> Option<Pair> {
+	if points.len() < 2 {
+		return None;
+	}
+
+	let extremes = |key: &dyn Fn(&Point<N>) -> i64| {
+		let min = (0..points.len()).min_by_key(|&i| key(&points[i]))?;
+		let max = (0..points.len()).max_by_key(|&i| key(&points[i]))?;
+		Some((min, max))
+	};
+
+	let pair = |first: usize, second: usize| Pair {
+		first: first.min(second),
+		second: first.max(second),
+		distance: metric.distance(&points[first], &points[second]),
+	};
+
+	let candidates: Vec<(usize, usize)> = match metric {
+		// |a - b|_1 is the largest difference of `s · p` over all sign
+		// vectors `s`, and `s` and `-s` give the same pair.
+		Metric::Manhattan => (0..1usize << N.saturating_sub(1))
+			.filter_map(|signs| {
+				extremes(&|point| {
+					point
+						.iter()
+						.enumerate()
+						.map(|(axis, &x)| match signs >> axis & 1 {
+							0 => x,
+							_ => -x,
+						})
+						.sum()
+				})
+			})
+			.collect(),
+		Metric::Chebyshev => (0..N)
+			.filter_map(|axis| extremes(&|point| point[axis]))
+			.collect(),
+		Metric::EuclideanSquared => (0..points.len())
+			.flat_map(|i| (i + 1..points.len()).map(move |j| (i, j)))
+			.collect(),
+	};
+
+	candidates
+		.into_iter()
+		.map(|(first, second)| pair(first, second))
+		.filter(|pair| pair.first != pair.second)
+		.max_by_key(|pair| pair.distance)
+		.or_else(|| Some(pair(0, 1)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const POINTS: [Point<2>; 6] =
+		[[0, 0], [10, 3], [-4, 7], [5, 5], [6, 4], [-20, -1]];
+
+	fn brute_force(metric: Metric) -> (u128, u128) {
+		let distances: Vec<u128> = (0..POINTS.len())
+			.flat_map(|i| (i + 1..POINTS.len()).map(move |j| (i, j)))
+			.map(|(i, j)| metric.distance(&POINTS[i], &POINTS[j]))
+			.collect();
+
+		(
+			*distances.iter().min().unwrap(),
+			*distances.iter().max().unwrap(),
+		)
+	}
+
+	#[test]
+	fn metrics() {
+		assert_eq!(manhattan(&[1, -2, 3], &[-1, 2, 3]), 6);
+		assert_eq!(chebyshev(&[1, -2, 3], &[-1, 2, 3]), 4);
+		assert_eq!(euclidean_squared(&[1, -2, 3], &[-1, 2, 3]), 20);
+	}
+
+	#[test]
+	fn large_coordinates() {
+		let a = [1 << 40, -(1 << 40)];
+		let b = [-(1 << 40), 1 << 40];
+		assert_eq!(manhattan(&a, &b), 1 << 42);
+		assert_eq!(euclidean_squared(&a, &b), 1 << 83);
+
+		let far = [i64::MIN, i64::MIN];
+		let near = [i64::MAX, i64::MAX];
+		assert_eq!(manhattan(&far, &near), 2 * u64::MAX as u128);
+		assert_eq!(euclidean_squared(&far, &near), u128::MAX);
+		assert_eq!(
+			nearest_pair(&[a, b, [0, 0]], Metric::EuclideanSquared)
+				.unwrap()
+				.distance,
+			1 << 81
+		);
+	}
+
+	#[test]
+	fn sums() {
+		let naive: u64 = (0..POINTS.len())
+			.flat_map(|i| (i + 1..POINTS.len()).map(move |j| (i, j)))
+			.map(|(i, j)| {
+				2 * POINTS[i][0].abs_diff(POINTS[j][0])
+					+ 3 * POINTS[i][1].abs_diff(POINTS[j][1])
+			})
+			.sum();
+
+		assert_eq!(manhattan_sum(&POINTS, [2, 3]), naive as u128);
+	}
+
+	#[test]
+	fn nearest_and_farthest() {
+		for metric in [
+			Metric::Manhattan,
+			Metric::Chebyshev,
+			Metric::EuclideanSquared,
+		] {
+			let (min, max) = brute_force(metric);
+			assert_eq!(nearest_pair(&POINTS, metric).unwrap().distance, min);
+			assert_eq!(farthest_pair(&POINTS, metric).unwrap().distance, max);
+		}
+
+		assert_eq!(
+			nearest_pair(&POINTS, Metric::Manhattan),
+			Some(Pair {
+				first: 3,
+				second: 4,
+				distance: 2
+			})
+		);
+		assert_eq!(nearest_pair(&POINTS[..1], Metric::Manhattan), None);
+	}
+}
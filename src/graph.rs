@@ -0,0 +1,139 @@
+use crate::intern::{Interner, Symbol};
+
+pub type NodeId = Symbol;
+
+/// Marks a missing edge in the adjacency lists.
+const NONE: u32 = u32::MAX;
+
+/// A directed graph over interned node labels, with an edge label of type
+/// `E` on every edge (e.g. `'L'` / `'R'`). Node names borrow from the
+/// input, and every node's edges are a linked list threaded through one
+/// flat `Vec`, so building the graph allocates a handful of times rather
+/// than once per node, and walking it never touches the labels.
+#[derive(Debug, Clone)]
+pub struct Graph<'a, E> {
+	names: Interner<'a>,
+	/// First and last edge out of each node, as indices into `edges`.
+	ends: Vec<(u32, u32)>,
+	/// Label, target, and the next edge out of the same node.
+	edges: Vec<(E, NodeId, u32)>,
+}
+
+impl<E> Default for Graph<'_, E> {
+	fn default() -> Self {
+		Self {
+			names: Interner::default(),
+			ends: Vec::new(),
+			edges: Vec::new(),
+		}
+	}
+}
+
+impl<'a, E> Graph<'a, E> {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn with_capacity(nodes: usize, edges: usize) -> Self {
+		Self {
+			names: Interner::with_capacity(nodes),
+			ends: Vec::with_capacity(nodes),
+			edges: Vec::with_capacity(edges),
+		}
+	}
+
+	pub fn add_node(&mut self, name: &'a str) -> NodeId {
+		let id = self.names.intern(name);
+		if self.ends.len() <= id as usize {
+			self.ends.resize(id as usize + 1, (NONE, NONE));
+		}
+		id
+	}
+
+	/// Adds an edge, creating both nodes if they don't exist yet.
+	pub fn add_edge(&mut self, from: &'a str, label: E, to: &'a str) {
+		let from = self.add_node(from);
+		let to = self.add_node(to);
+
+		let index = self.edges.len() as u32;
+		self.edges.push((label, to, NONE));
+
+		let (first, last) = &mut self.ends[from as usize];
+		if *last == NONE {
+			*first = index;
+		} else {
+			self.edges[*last as usize].2 = index;
+		}
+		*last = index;
+	}
+
+	pub fn id(&self, name: &str) -> Option<NodeId> {
+		self.names.id(name)
+	}
+
+	pub fn name(&self, id: NodeId) -> &'a str {
+		self.names.name(id)
+	}
+
+	pub fn len(&self) -> usize {
+		self.ends.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.ends.is_empty()
+	}
+
+	pub fn nodes(&self) -> impl Iterator<Item = NodeId> {
+		0..self.ends.len() as NodeId
+	}
+
+	/// Outgoing edges of `id` in insertion order.
+	pub fn neighbors(
+		&self,
+		id: NodeId,
+	) -> impl Iterator<Item = (&E, NodeId)> + '_ {
+		let mut next = self.ends[id as usize].0;
+		std::iter::from_fn(move || {
+			let (label, to, after) = self.edges.get(next as usize)?;
+			next = *after;
+			Some((label, *to))
+		})
+	}
+
+	/// Target of the first edge out of `id` with the given label.
+	pub fn edge(&self, id: NodeId, label: &E) -> Option<NodeId>
+	where
+		E: PartialEq,
+	{
+		self.neighbors(id)
+			.find_map(|(l, to)| (l == label).then_some(to))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn labeled_edges() {
+		let mut graph = Graph::new();
+		graph.add_edge("AAA", 'L', "BBB");
+		graph.add_edge("AAA", 'R', "CCC");
+		graph.add_edge("BBB", 'L', "AAA");
+
+		let aaa = graph.id("AAA").unwrap();
+		let bbb = graph.id("BBB").unwrap();
+		let ccc = graph.id("CCC").unwrap();
+
+		assert_eq!((aaa, bbb, ccc), (0, 1, 2));
+		assert_eq!(graph.name(ccc), "CCC");
+		assert_eq!(graph.edge(aaa, &'R'), Some(ccc));
+		assert_eq!(graph.edge(bbb, &'R'), None);
+		assert_eq!(
+			graph.neighbors(aaa).collect::<Vec<_>>(),
+			vec![(&'L', bbb), (&'R', ccc)]
+		);
+		assert_eq!(graph.neighbors(ccc).count(), 0);
+		assert_eq!(graph.len(), 3);
+	}
+}
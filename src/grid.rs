@@ -0,0 +1,437 @@
+use anyhow::{anyhow, Result};
+use std::{
+	collections::hash_map::DefaultHasher,
+	hash::{Hash, Hasher},
+	ops::{Index, IndexMut},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+	North,
+	South,
+	East,
+	West,
+}
+
+impl Direction {
+	pub const ALL: [Direction; 4] = [
+		Direction::North,
+		Direction::East,
+		Direction::South,
+		Direction::West,
+	];
+
+	pub fn opposite(self) -> Self {
+		match self {
+			Direction::North => Direction::South,
+			Direction::South => Direction::North,
+			Direction::East => Direction::West,
+			Direction::West => Direction::East,
+		}
+	}
+}
+
+/// A dense, row-major 2D grid. Positions are `(x, y)` with `x` the column.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+	width: usize,
+	height: usize,
+	cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+	pub fn new(width: usize, height: usize, fill: T) -> Self
+	where
+		T: Clone,
+	{
+		Self {
+			width,
+			height,
+			cells: vec![fill; width * height],
+		}
+	}
+
+	pub fn from_cells(width: usize, height: usize, cells: Vec<T>) -> Self {
+		assert_eq!(cells.len(), width * height, "Grid size mismatch");
+
+		Self {
+			width,
+			height,
+			cells,
+		}
+	}
+
+	pub fn width(&self) -> usize {
+		self.width
+	}
+
+	pub fn height(&self) -> usize {
+		self.height
+	}
+
+	pub fn cells(&self) -> &[T] {
+		&self.cells
+	}
+
+	pub fn get(&self, (x, y): (usize, usize)) -> Option<&T> {
+		(x < self.width && y < self.height)
+			.then(|| &self.cells[y * self.width + x])
+	}
+
+	pub fn get_mut(&mut self, (x, y): (usize, usize)) -> Option<&mut T> {
+		(x < self.width && y < self.height)
+			.then(|| &mut self.cells[y * self.width + x])
+	}
+
+	pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+		self.cells.chunks(self.width.max(1))
+	}
+
+	/// Positions and cells in row-major order.
+	pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+		let width = self.width;
+
+		self.cells
+			.iter()
+			.enumerate()
+			.map(move |(index, cell)| ((index % width, index / width), cell))
+	}
+
+	/// The position one step from `(x, y)` in `direction`, if it is on the
+	/// grid.
+	pub fn step(
+		&self,
+		(x, y): (usize, usize),
+		direction: Direction,
+	) -> Option<(usize, usize)> {
+		let (x, y) = match direction {
+			Direction::North => (x, y.checked_sub(1)?),
+			Direction::South => (x, y + 1),
+			Direction::East => (x + 1, y),
+			Direction::West => (x.checked_sub(1)?, y),
+		};
+
+		(x < self.width && y < self.height).then_some((x, y))
+	}
+
+	/// Positions orthogonally adjacent to `position` that are on the grid.
+	pub fn neighbors(
+		&self,
+		position: (usize, usize),
+	) -> impl Iterator<Item = (usize, usize)> + '_ {
+		Direction::ALL
+			.into_iter()
+			.filter_map(move |direction| self.step(position, direction))
+	}
+
+	pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+		Grid {
+			width: self.width,
+			height: self.height,
+			cells: self.cells.iter().map(f).collect(),
+		}
+	}
+
+	pub fn transposed(&self) -> Self
+	where
+		T: Clone,
+	{
+		let cells = (0..self.width)
+			.flat_map(|x| {
+				(0..self.height)
+					.map(move |y| self.cells[y * self.width + x].clone())
+			})
+			.collect();
+
+		Self {
+			width: self.height,
+			height: self.width,
+			cells,
+		}
+	}
+
+	pub fn rotated_cw(&self) -> Self
+	where
+		T: Clone,
+	{
+		let mut grid = self.transposed();
+		grid.flip_horizontal();
+		grid
+	}
+
+	pub fn rotated_ccw(&self) -> Self
+	where
+		T: Clone,
+	{
+		let mut grid = self.transposed();
+		grid.flip_vertical();
+		grid
+	}
+
+	pub fn flipped_horizontal(&self) -> Self
+	where
+		T: Clone,
+	{
+		let mut grid = self.clone();
+		grid.flip_horizontal();
+		grid
+	}
+
+	pub fn flipped_vertical(&self) -> Self
+	where
+		T: Clone,
+	{
+		let mut grid = self.clone();
+		grid.flip_vertical();
+		grid
+	}
+
+	/// Transposes without allocating. Square grids swap across the diagonal,
+	/// other shapes rotate each permutation cycle from its smallest index.
+	pub fn transpose(&mut self) {
+		let (width, height) = (self.width, self.height);
+
+		if width == height {
+			for y in 0..height {
+				for x in y + 1..width {
+					self.cells.swap(y * width + x, x * width + y);
+				}
+			}
+		} else {
+			let len = self.cells.len();
+			// Cell at `index` moves to `target(index)`.
+			let target =
+				|index: usize| (index % width) * height + index / width;
+
+			for start in 1..len.saturating_sub(1) {
+				let mut next = target(start);
+				while next > start {
+					next = target(next);
+				}
+
+				if next == start {
+					let mut current = target(start);
+					while current != start {
+						self.cells.swap(start, current);
+						current = target(current);
+					}
+				}
+			}
+		}
+
+		self.width = height;
+		self.height = width;
+	}
+
+	pub fn rotate_cw(&mut self) {
+		self.transpose();
+		self.flip_horizontal();
+	}
+
+	pub fn rotate_ccw(&mut self) {
+		self.transpose();
+		self.flip_vertical();
+	}
+
+	pub fn rotate_180(&mut self) {
+		self.cells.reverse();
+	}
+
+	/// Mirrors left to right.
+	pub fn flip_horizontal(&mut self) {
+		for row in self.cells.chunks_mut(self.width.max(1)) {
+			row.reverse();
+		}
+	}
+
+	/// Mirrors top to bottom.
+	pub fn flip_vertical(&mut self) {
+		for y in 0..self.height / 2 {
+			let (top, bottom) = self.cells.split_at_mut((y + 1) * self.width);
+			let mirrored = (self.height - 2 - 2 * y) * self.width;
+			top[y * self.width..]
+				.swap_with_slice(&mut bottom[mirrored..mirrored + self.width]);
+		}
+	}
+
+	/// Slides every rolling cell towards `direction` until it hits the edge,
+	/// a cell that is neither empty nor rolling, or another rolling cell that
+	/// has already stopped.
+	pub fn tilt(
+		&mut self,
+		direction: Direction,
+		is_rolling: impl Fn(&T) -> bool,
+		is_empty: impl Fn(&T) -> bool,
+	) {
+		let (width, height) = (self.width, self.height);
+
+		let (lanes, length) = match direction {
+			Direction::North | Direction::South => (width, height),
+			Direction::East | Direction::West => (height, width),
+		};
+
+		// Index of the `k`-th cell of `lane`, counted from the side the
+		// cells slide towards.
+		let index = |lane: usize, k: usize| match direction {
+			Direction::North => k * width + lane,
+			Direction::South => (height - 1 - k) * width + lane,
+			Direction::West => lane * width + k,
+			Direction::East => lane * width + width - 1 - k,
+		};
+
+		for lane in 0..lanes {
+			let mut free = 0;
+
+			for k in 0..length {
+				let cell = &self.cells[index(lane, k)];
+
+				if is_rolling(cell) {
+					self.cells.swap(index(lane, free), index(lane, k));
+					free += 1;
+				} else if !is_empty(cell) {
+					free = k + 1;
+				}
+			}
+		}
+	}
+
+	/// Hash of the dimensions and cells, for spotting repeated states.
+	pub fn state_hash(&self) -> u64
+	where
+		T: Hash,
+	{
+		let mut hasher = DefaultHasher::new();
+		self.hash(&mut hasher);
+		hasher.finish()
+	}
+}
+
+impl Grid<u8> {
+	/// Parses newline separated rows of bytes, ignoring a trailing newline.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+		let bytes = bytes.strip_suffix(b"\n").unwrap_or(bytes);
+
+		let mut width = None;
+		let mut height = 0;
+		let mut cells = Vec::with_capacity(bytes.len());
+
+		for (y, row) in bytes
+			.split(|&b| b == b'\n')
+			.enumerate()
+			.take_while(|_| !bytes.is_empty())
+		{
+			let row = row.strip_suffix(b"\r").unwrap_or(row);
+
+			match width {
+				None => width = Some(row.len()),
+				Some(width) if width != row.len() => {
+					return Err(anyhow!(
+						"Invalid grid: row {} has width {}, expected {}",
+						y + 1,
+						row.len(),
+						width
+					));
+				}
+				_ => {}
+			}
+
+			cells.extend_from_slice(row);
+			height += 1;
+		}
+
+		Ok(Self {
+			width: width.unwrap_or(0),
+			height,
+			cells,
+		})
+	}
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+	type Output = T;
+
+	fn index(&self, position: (usize, usize)) -> &Self::Output {
+		self.get(position).expect("Position out of bounds for Grid")
+	}
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+	fn index_mut(&mut self, position: (usize, usize)) -> &mut Self::Output {
+		self.get_mut(position)
+			.expect("Position out of bounds for Grid")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::inputs;
+
+	fn check_round_trips(original: &Grid<u8>) {
+		let mut grid = original.clone();
+
+		for _ in 0..4 {
+			grid.rotate_cw();
+		}
+		assert_eq!(&grid, original);
+
+		grid.rotate_ccw();
+		assert_eq!(grid, original.rotated_ccw());
+		grid.rotate_cw();
+		assert_eq!(&grid, original);
+
+		grid.transpose();
+		assert_eq!(grid, original.transposed());
+		grid.transpose();
+		assert_eq!(&grid, original);
+
+		grid.flip_horizontal();
+		grid.flip_vertical();
+		assert_eq!(grid, original.rotated_cw().rotated_cw());
+		grid.rotate_180();
+		assert_eq!(&grid, original);
+
+		assert_eq!(&original.flipped_vertical().flipped_vertical(), original);
+		assert_eq!(grid.state_hash(), original.state_hash());
+		assert_ne!(original.rotated_cw().state_hash(), original.state_hash());
+	}
+
+	#[test]
+	fn day_10_round_trip() {
+		let grid =
+			Grid::from_bytes(&inputs::read("inputs/day_10.txt").unwrap());
+		check_round_trips(&grid.unwrap());
+	}
+
+	#[test]
+	fn day_11_round_trip() {
+		let grid =
+			Grid::from_bytes(&inputs::read("inputs/day_11.txt").unwrap());
+		check_round_trips(&grid.unwrap());
+	}
+
+	#[test]
+	fn non_square() {
+		let grid = Grid::from_bytes(b"abc\ndef\n").unwrap();
+		assert_eq!(grid.rotated_cw(), Grid::from_bytes(b"da\neb\nfc").unwrap());
+		check_round_trips(&grid);
+	}
+
+	#[test]
+	fn tilt() {
+		let mut grid = Grid::from_bytes(b"O.#.\n.O.O\n..O.\nO#..").unwrap();
+		let rolling = |c: &u8| *c == b'O';
+		let empty = |c: &u8| *c == b'.';
+
+		grid.tilt(Direction::North, rolling, empty);
+		assert_eq!(grid, Grid::from_bytes(b"OO#O\nO.O.\n....\n.#..").unwrap());
+
+		grid.tilt(Direction::East, rolling, empty);
+		assert_eq!(grid, Grid::from_bytes(b"OO#O\n..OO\n....\n.#..").unwrap());
+	}
+
+	#[test]
+	fn ragged_rows() {
+		assert!(Grid::from_bytes(b"...\n..\n").is_err());
+	}
+}
@@ -0,0 +1,316 @@
+use crate::cli;
+use anyhow::{anyhow, bail, Context, Result};
+use chacha20poly1305::{
+	aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+	ChaCha20Poly1305, Nonce,
+};
+use std::{
+	borrow::Cow,
+	env, fs,
+	path::{Component, Path},
+};
+
+// Inputs written into the binary by the build script, as `(path, bytes)`.
+// Empty unless built with the `embed-inputs` feature.
+include!(concat!(env!("OUT_DIR"), "/inputs.rs"));
+
+/// Holds the key as 64 hex digits.
+pub const KEY_VAR: &str = "AOC_INPUT_KEY";
+/// Names a file holding the key, instead of [`KEY_FILE`].
+pub const KEY_FILE_VAR: &str = "AOC_INPUT_KEY_FILE";
+/// Where the key is read from when neither variable is set.
+pub const KEY_FILE: &str = ".aoc-key";
+
+/// The profile whose inputs are in `inputs/` itself.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// The input set chosen with `--profile=NAME`, for checking solutions
+/// against other people's inputs.
+pub fn profile() -> Result<String> {
+	match cli::option("profile") {
+		Some(name) => profile_name(name),
+		None => Ok(DEFAULT_PROFILE.to_string()),
+	}
+}
+
+/// `name` if it is a plain directory name, so a profile like `../..` can't
+/// read from outside `inputs/`.
+fn profile_name(name: String) -> Result<String> {
+	let mut components = Path::new(&name).components();
+	match (components.next(), components.next()) {
+		(Some(Component::Normal(part)), None) if part == name.as_str() => {
+			Ok(name)
+		}
+		_ => bail!("Invalid profile `{name}`: expected a directory name"),
+	}
+}
+
+/// Where a profile keeps its inputs and its `answers.txt`.
+pub fn directory(profile: &str) -> String {
+	match profile {
+		DEFAULT_PROFILE => "inputs".to_string(),
+		_ => format!("inputs/{profile}"),
+	}
+}
+
+/// `path` in the chosen profile, so `inputs/day_01.txt` is read from
+/// `inputs/NAME/day_01.txt`.
+pub fn locate(path: &str) -> Result<String> {
+	Ok(match path.strip_prefix("inputs/") {
+		Some(name) => format!("{}/{name}", directory(&profile()?)),
+		None => path.to_string(),
+	})
+}
+
+/// Starts every encrypted input, followed by the nonce and the ciphertext.
+const MAGIC: &[u8] = b"AOC1";
+const NONCE_LEN: usize = 12;
+
+/// A ChaCha20-Poly1305 key for encrypting inputs at rest.
+pub struct Key(chacha20poly1305::Key);
+
+impl Key {
+	pub fn generate() -> Self {
+		Self(ChaCha20Poly1305::generate_key(&mut OsRng))
+	}
+
+	pub fn from_hex(hex: &str) -> Result<Self> {
+		let hex = hex.trim();
+		if hex.len() != 64 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+			bail!("Expected 64 hex digits");
+		}
+
+		let bytes: Vec<u8> = (0..hex.len())
+			.step_by(2)
+			.map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+			.collect();
+		Ok(Self(*chacha20poly1305::Key::from_slice(&bytes)))
+	}
+
+	pub fn to_hex(&self) -> String {
+		self.0.iter().map(|byte| format!("{byte:02x}")).collect()
+	}
+
+	/// The key in `$AOC_INPUT_KEY`, or else in the file named by
+	/// `$AOC_INPUT_KEY_FILE`, which defaults to `.aoc-key`.
+	pub fn load() -> Result<Self> {
+		if let Ok(hex) = env::var(KEY_VAR) {
+			return Self::from_hex(&hex)
+				.with_context(|| format!("Invalid key in ${KEY_VAR}"));
+		}
+
+		let file =
+			env::var(KEY_FILE_VAR).unwrap_or_else(|_| KEY_FILE.to_string());
+		let hex = fs::read_to_string(&file)
+			.with_context(|| format!("No key in ${KEY_VAR} or in `{file}`"))?;
+		Self::from_hex(&hex).with_context(|| format!("Invalid key in `{file}`"))
+	}
+}
+
+/// Where the encrypted copy of `path` is kept.
+pub fn encrypted_path(path: &str) -> String {
+	format!("{path}.enc")
+}
+
+/// What an encrypted input is bound to: its path within `inputs/`, like
+/// `alice/day_01.txt`, so it can't be swapped for another day's input or
+/// for the same day of another profile.
+fn bound_path(path: &str) -> &str {
+	path.strip_prefix("inputs/").unwrap_or(path)
+}
+
+/// Encrypts the input at `path`, under a fresh random nonce.
+pub fn encrypt(key: &Key, path: &str, input: &[u8]) -> Vec<u8> {
+	let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+	let payload = Payload {
+		msg: input,
+		aad: bound_path(path).as_bytes(),
+	};
+	let ciphertext = ChaCha20Poly1305::new(&key.0)
+		.encrypt(&nonce, payload)
+		.expect("Inputs are far below the cipher's size limit");
+
+	[MAGIC, nonce.as_slice(), &ciphertext].concat()
+}
+
+/// Decrypts what [`encrypt`] made of the input at `path`, checking that it
+/// is intact and belongs to `path`.
+pub fn decrypt(key: &Key, path: &str, sealed: &[u8]) -> Result<Vec<u8>> {
+	let rest = sealed
+		.strip_prefix(MAGIC)
+		.ok_or_else(|| anyhow!("Not an encrypted input"))?;
+	if rest.len() < NONCE_LEN {
+		bail!("Encrypted input is truncated");
+	}
+	let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+	let payload = Payload {
+		msg: ciphertext,
+		aad: bound_path(path).as_bytes(),
+	};
+
+	ChaCha20Poly1305::new(&key.0)
+		.decrypt(Nonce::from_slice(nonce), payload)
+		.map_err(|_| anyhow!("Wrong key, or the file was changed or renamed"))
+}
+
+/// The copy of `path` built into the binary, if any.
+pub fn embedded(path: &str) -> Option<&'static [u8]> {
+	EMBEDDED
+		.iter()
+		.find(|&&(embedded, _)| embedded == path)
+		.map(|&(_, bytes)| bytes)
+}
+
+/// Whether there is an input at `path`, built into the binary or on disk,
+/// in plain text or encrypted.
+pub fn exists(path: &str) -> bool {
+	let sealed_path = encrypted_path(path);
+	[path, sealed_path.as_str()]
+		.iter()
+		.any(|path| embedded(path).is_some() || Path::new(path).exists())
+}
+
+/// The input at `path` in the chosen profile, taken from the binary when it
+/// was embedded and read from disk otherwise. Inputs only stored encrypted,
+/// as `path.enc`, are decrypted with the [`Key::load`] key.
+pub fn read(path: &str) -> Result<Cow<'static, [u8]>> {
+	read_with(&locate(path)?, Key::load)
+}
+
+fn read_with(
+	path: &str,
+	key: impl FnOnce() -> Result<Key>,
+) -> Result<Cow<'static, [u8]>> {
+	if let Some(bytes) = embedded(path) {
+		return Ok(Cow::Borrowed(bytes));
+	}
+	let error = match fs::read(path) {
+		Ok(bytes) => return Ok(Cow::Owned(bytes)),
+		Err(error) => error,
+	};
+
+	let sealed_path = encrypted_path(path);
+	let sealed = match embedded(&sealed_path) {
+		Some(bytes) => Cow::Borrowed(bytes),
+		None => match fs::read(&sealed_path) {
+			Ok(bytes) => Cow::Owned(bytes),
+			Err(_) => {
+				return Err(error)
+					.with_context(|| format!("Can't read `{path}`"));
+			}
+		},
+	};
+
+	let key = key().with_context(|| {
+		format!("`{path}` is only stored encrypted, as `{sealed_path}`")
+	})?;
+	decrypt(&key, path, &sealed)
+		.map(Cow::Owned)
+		.with_context(|| format!("Can't decrypt `{sealed_path}`"))
+}
+
+/// Like [`read`], for inputs that must be UTF-8.
+pub fn read_to_string(path: &str) -> Result<Cow<'static, str>> {
+	Ok(match read(path)? {
+		Cow::Borrowed(bytes) => Cow::Borrowed(
+			std::str::from_utf8(bytes)
+				.with_context(|| format!("`{path}` isn't UTF-8"))?,
+		),
+		Cow::Owned(bytes) => Cow::Owned(
+			String::from_utf8(bytes)
+				.with_context(|| format!("`{path}` isn't UTF-8"))?,
+		),
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn reads_inputs() {
+		// Stored encrypted, or in plain text once decrypted for editing.
+		let input = read_to_string("inputs/day_01.txt").unwrap();
+		assert_eq!(input.lines().count(), 1000);
+		assert_eq!(
+			embedded("inputs/day_01.txt")
+				.or_else(|| embedded("inputs/day_01.txt.enc"))
+				.is_some(),
+			cfg!(feature = "embed-inputs")
+		);
+
+		assert!(exists("inputs/day_01.txt"));
+		assert!(!exists("inputs/day_99.txt"));
+
+		assert_eq!(locate("inputs/day_01.txt").unwrap(), "inputs/day_01.txt");
+		assert_eq!(directory("alice"), "inputs/alice");
+
+		let error = read("inputs/day_99.txt").unwrap_err();
+		assert_eq!(error.to_string(), "Can't read `inputs/day_99.txt`");
+	}
+
+	#[test]
+	fn profile_names() {
+		assert_eq!(profile_name("alice".into()).unwrap(), "alice");
+		for name in ["", ".", "..", "../..", "a/b", "alice/", "/tmp"] {
+			assert!(profile_name(name.into()).is_err(), "{name}");
+		}
+	}
+
+	#[test]
+	fn round_trip() {
+		let key = Key::generate();
+		let sealed = encrypt(&key, "inputs/day_01.txt", b"1abc2\n");
+		assert!(sealed.starts_with(MAGIC));
+		assert!(!sealed.windows(5).any(|window| window == b"1abc2"));
+		assert_ne!(sealed, encrypt(&key, "inputs/day_01.txt", b"1abc2\n"));
+
+		let opened = decrypt(&key, "inputs/day_01.txt", &sealed).unwrap();
+		assert_eq!(opened, b"1abc2\n");
+
+		let path = "inputs/day_01.txt";
+		assert!(decrypt(&Key::generate(), path, &sealed).is_err());
+		assert!(decrypt(&key, "inputs/day_02.txt", &sealed).is_err());
+		assert!(decrypt(&key, "inputs/alice/day_01.txt", &sealed).is_err());
+		let mut tampered = sealed.clone();
+		*tampered.last_mut().unwrap() ^= 1;
+		assert!(decrypt(&key, path, &tampered).is_err());
+		assert!(decrypt(&key, path, b"AOC1").is_err());
+
+		let alice = encrypt(&key, "inputs/alice/day_01.txt", b"1abc2\n");
+		assert!(decrypt(&key, "inputs/alice/day_01.txt", &alice).is_ok());
+		assert!(decrypt(&key, "inputs/bob/day_01.txt", &alice).is_err());
+	}
+
+	#[test]
+	fn keys() {
+		let key = Key::generate();
+		let hex = key.to_hex();
+		assert_eq!(hex.len(), 64);
+		assert_eq!(Key::from_hex(&format!("{hex}\n")).unwrap().to_hex(), hex);
+		assert!(Key::from_hex(&hex[1..]).is_err());
+		assert!(Key::from_hex(&hex.replace(&hex[..1], "g")).is_err());
+	}
+
+	#[test]
+	fn reads_encrypted_inputs() {
+		let directory =
+			env::temp_dir().join(format!("aoc-{}", std::process::id()));
+		fs::create_dir_all(&directory).unwrap();
+		let path = directory.join("day_07.txt");
+		let path = path.to_str().unwrap();
+
+		let key = Key::generate();
+		let hex = key.to_hex();
+		fs::write(encrypted_path(path), encrypt(&key, path, b"32T3K 765\n"))
+			.unwrap();
+
+		let input = read_with(path, || Key::from_hex(&hex)).unwrap();
+		assert_eq!(&*input, b"32T3K 765\n");
+
+		let error = read_with(path, || bail!("No key")).unwrap_err();
+		assert!(format!("{error:#}").contains("only stored encrypted"));
+
+		fs::remove_dir_all(directory).unwrap();
+	}
+}
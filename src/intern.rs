@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+
+pub type Symbol = u32;
+
+/// Assigns dense ids to names in the order they are first seen. Names are
+/// borrowed, usually from the input buffer, so interning never copies them.
+#[derive(Debug, Clone, Default)]
+pub struct Interner<'a> {
+	ids: HashMap<&'a str, Symbol>,
+	names: Vec<&'a str>,
+}
+
+impl<'a> Interner<'a> {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// An interner with room for `capacity` names before it reallocates.
+	pub fn with_capacity(capacity: usize) -> Self {
+		Self {
+			ids: HashMap::with_capacity(capacity),
+			names: Vec::with_capacity(capacity),
+		}
+	}
+
+	pub fn intern(&mut self, name: &'a str) -> Symbol {
+		let next = self.names.len() as Symbol;
+		let id = *self.ids.entry(name).or_insert(next);
+		if id == next {
+			self.names.push(name);
+		}
+		id
+	}
+
+	pub fn id(&self, name: &str) -> Option<Symbol> {
+		self.ids.get(name).copied()
+	}
+
+	pub fn name(&self, id: Symbol) -> &'a str {
+		self.names[id as usize]
+	}
+
+	/// Every name, in id order.
+	pub fn names(&self) -> &[&'a str] {
+		&self.names
+	}
+
+	pub fn len(&self) -> usize {
+		self.names.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.names.is_empty()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn dense_ids() {
+		let input = String::from("b a b c a");
+		let mut interner = Interner::new();
+		let ids: Vec<_> =
+			input.split(' ').map(|name| interner.intern(name)).collect();
+
+		assert_eq!(ids, [0, 1, 0, 2, 1]);
+		assert_eq!(interner.names(), ["b", "a", "c"]);
+		assert_eq!(interner.id("c"), Some(2));
+		assert_eq!(interner.id("d"), None);
+		assert!(std::ptr::eq(interner.name(0), &input[..1]));
+	}
+}
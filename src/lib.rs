@@ -1 +1,28 @@
+extern crate self as aoc;
 
+pub mod alloc;
+pub mod animation;
+pub mod answer;
+#[cfg(feature = "bigint")]
+pub mod bigint;
+pub mod check;
+pub mod cli;
+pub mod compress;
+pub mod cycle;
+pub mod diagnostic;
+pub mod distance;
+pub mod graph;
+pub mod grid;
+pub mod inputs;
+pub mod intern;
+pub mod math;
+pub mod memo;
+pub mod network;
+pub mod parse;
+pub mod path;
+pub mod piecewise;
+pub mod poly;
+pub mod range;
+pub mod render;
+pub mod sparse;
+pub mod stream;
@@ -0,0 +1,334 @@
+use std::fmt::{self, Debug, Display};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MathError {
+	/// An intermediate or final value doesn't fit in the integer type.
+	Overflow,
+	/// The equation or system has no solution.
+	NoSolution,
+	/// A modulus was zero or negative.
+	InvalidModulus,
+}
+
+impl Display for MathError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			MathError::Overflow => write!(f, "Arithmetic overflow"),
+			MathError::NoSolution => write!(f, "No solution"),
+			MathError::InvalidModulus => write!(f, "Modulus must be positive"),
+		}
+	}
+}
+
+impl std::error::Error for MathError {}
+
+pub type Result<T> = std::result::Result<T, MathError>;
+
+/// The primitive integer operations the generic functions need.
+pub trait Integer: Copy + Ord + Debug + Display {
+	const ZERO: Self;
+	const ONE: Self;
+
+	fn checked_add(self, other: Self) -> Option<Self>;
+	fn checked_sub(self, other: Self) -> Option<Self>;
+	fn checked_mul(self, other: Self) -> Option<Self>;
+	fn checked_div(self, other: Self) -> Option<Self>;
+	fn checked_rem(self, other: Self) -> Option<Self>;
+	fn checked_abs(self) -> Option<Self>;
+}
+
+macro_rules! impl_integer {
+	(@abs unsigned $x:ident) => {
+		Some($x)
+	};
+	(@abs signed $x:ident) => {
+		$x.checked_abs()
+	};
+	($kind:ident: $($t:ty),*) => {
+		$(impl Integer for $t {
+			const ZERO: Self = 0;
+			const ONE: Self = 1;
+
+			fn checked_add(self, other: Self) -> Option<Self> {
+				<$t>::checked_add(self, other)
+			}
+
+			fn checked_sub(self, other: Self) -> Option<Self> {
+				<$t>::checked_sub(self, other)
+			}
+
+			fn checked_mul(self, other: Self) -> Option<Self> {
+				<$t>::checked_mul(self, other)
+			}
+
+			fn checked_div(self, other: Self) -> Option<Self> {
+				<$t>::checked_div(self, other)
+			}
+
+			fn checked_rem(self, other: Self) -> Option<Self> {
+				<$t>::checked_rem(self, other)
+			}
+
+			fn checked_abs(self) -> Option<Self> {
+				let x = self;
+				impl_integer!(@abs $kind x)
+			}
+		})*
+	};
+}
+
+impl_integer!(unsigned: u8, u16, u32, u64, u128, usize);
+impl_integer!(signed: i8, i16, i32, i64, i128, isize);
+
+/// Greatest common divisor, always non-negative. `gcd(0, 0)` is 0.
+pub fn gcd<T: Integer>(a: T, b: T) -> Result<T> {
+	let mut a = a.checked_abs().ok_or(MathError::Overflow)?;
+	let mut b = b.checked_abs().ok_or(MathError::Overflow)?;
+
+	while b != T::ZERO {
+		let r = a.checked_rem(b).ok_or(MathError::Overflow)?;
+		a = b;
+		b = r;
+	}
+
+	Ok(a)
+}
+
+/// Least common multiple, always non-negative. It is 0 if either is 0.
+pub fn lcm<T: Integer>(a: T, b: T) -> Result<T> {
+	if a == T::ZERO || b == T::ZERO {
+		return Ok(T::ZERO);
+	}
+
+	let a = a.checked_abs().ok_or(MathError::Overflow)?;
+	let b = b.checked_abs().ok_or(MathError::Overflow)?;
+
+	a.checked_div(gcd(a, b)?)
+		.and_then(|a| a.checked_mul(b))
+		.ok_or(MathError::Overflow)
+}
+
+/// `gcd` of every number, 0 for an empty slice.
+pub fn gcd_all<T: Integer>(numbers: &[T]) -> Result<T> {
+	numbers.iter().try_fold(T::ZERO, |acc, &x| gcd(acc, x))
+}
+
+/// `lcm` of every number, 1 for an empty slice.
+pub fn lcm_all<T: Integer>(numbers: &[T]) -> Result<T> {
+	numbers.iter().try_fold(T::ONE, |acc, &x| lcm(acc, x))
+}
+
+/// Returns `(g, x, y)` with `a * x + b * y == g == gcd(a, b)`.
+pub fn extended_gcd(a: i128, b: i128) -> Result<(i128, i128, i128)> {
+	let (mut old_r, mut r) = (a, b);
+	let (mut old_x, mut x) = (1i128, 0i128);
+	let (mut old_y, mut y) = (0i128, 1i128);
+
+	while r != 0 {
+		let q = old_r.checked_div(r).ok_or(MathError::Overflow)?;
+		let step = |old: i128, new: i128| {
+			q.checked_mul(new)
+				.and_then(|qn| old.checked_sub(qn))
+				.ok_or(MathError::Overflow)
+		};
+
+		(old_r, r) = (r, step(old_r, r)?);
+		(old_x, x) = (x, step(old_x, x)?);
+		(old_y, y) = (y, step(old_y, y)?);
+	}
+
+	if old_r < 0 {
+		let negate = |v: i128| v.checked_neg().ok_or(MathError::Overflow);
+		return Ok((negate(old_r)?, negate(old_x)?, negate(old_y)?));
+	}
+
+	Ok((old_r, old_x, old_y))
+}
+
+/// `x` in `0..modulus` with `a * x ≡ 1 (mod modulus)`.
+pub fn mod_inverse(a: i128, modulus: i128) -> Result<i128> {
+	if modulus <= 0 {
+		return Err(MathError::InvalidModulus);
+	}
+
+	let (g, x, _) = extended_gcd(a.rem_euclid(modulus), modulus)?;
+
+	match g {
+		1 => Ok(x.rem_euclid(modulus)),
+		_ => Err(MathError::NoSolution),
+	}
+}
+
+/// `base^exponent mod modulus`, in `0..modulus`.
+pub fn mod_pow(base: i128, exponent: u128, modulus: i128) -> Result<i128> {
+	if modulus <= 0 {
+		return Err(MathError::InvalidModulus);
+	}
+
+	let mul = |a: i128, b: i128| {
+		a.checked_mul(b)
+			.map(|p| p.rem_euclid(modulus))
+			.ok_or(MathError::Overflow)
+	};
+
+	let mut result = 1 % modulus;
+	let mut base = base.rem_euclid(modulus);
+	let mut exponent = exponent;
+
+	while exponent > 0 {
+		if exponent & 1 == 1 {
+			result = mul(result, base)?;
+		}
+		base = mul(base, base)?;
+		exponent >>= 1;
+	}
+
+	Ok(result)
+}
+
+/// Chinese Remainder Theorem over `(remainder, modulus)` congruences, which
+/// don't need to be pairwise coprime. Returns the smallest non-negative
+/// solution and the lcm of the moduli every solution repeats with.
+pub fn crt(congruences: &[(i128, i128)]) -> Result<(i128, i128)> {
+	congruences.iter().try_fold((0, 1), |(r1, m1), &(r2, m2)| {
+		if m2 <= 0 {
+			return Err(MathError::InvalidModulus);
+		}
+
+		let r2 = r2.rem_euclid(m2);
+		let g = gcd(m1, m2)?;
+		let diff = r2.checked_sub(r1).ok_or(MathError::Overflow)?;
+
+		if diff % g != 0 {
+			return Err(MathError::NoSolution);
+		}
+
+		let m2_g = m2 / g;
+		let modulus = lcm(m1, m2)?;
+		let k = mod_inverse(m1 / g, m2_g)?;
+		let k = ((diff / g).rem_euclid(m2_g))
+			.checked_mul(k)
+			.ok_or(MathError::Overflow)?
+			.rem_euclid(m2_g);
+		let remainder = m1
+			.checked_mul(k)
+			.and_then(|x| x.checked_add(r1))
+			.ok_or(MathError::Overflow)?
+			.rem_euclid(modulus);
+
+		Ok((remainder, modulus))
+	})
+}
+
+/// Number of integers `x` with `x^2 + b*x + c < 0`, the integers strictly
+/// between the two roots. Exact even when the roots are integers.
+pub fn count_between_roots(b: i128, c: i128) -> Result<u128> {
+	let f = |x: i128| {
+		x.checked_add(b)
+			.and_then(|sum| sum.checked_mul(x))
+			.and_then(|product| product.checked_add(c))
+			.ok_or(MathError::Overflow)
+	};
+
+	let discriminant = b
+		.checked_mul(b)
+		.and_then(|square| square.checked_sub(c.checked_mul(4)?))
+		.ok_or(MathError::Overflow)?;
+	if discriminant <= 0 {
+		return Ok(0);
+	}
+
+	// The integer minimum is next to the vertex at -b/2.
+	let vertex = b.checked_neg().ok_or(MathError::Overflow)?.div_euclid(2);
+	let inside = if f(vertex)? < 0 {
+		vertex
+	} else if f(vertex + 1)? < 0 {
+		vertex + 1
+	} else {
+		return Ok(0);
+	};
+
+	// The estimates are off by at most one either way, so nudge them onto
+	// the last integers inside the roots.
+	let root = discriminant.isqrt();
+	let mut low = ((-b - root).div_euclid(2)).min(inside);
+	while f(low)? >= 0 {
+		low += 1;
+	}
+	while f(low - 1)? < 0 {
+		low -= 1;
+	}
+
+	let mut high = ((-b + root).div_euclid(2) + 1).max(inside);
+	while f(high)? >= 0 {
+		high -= 1;
+	}
+	while f(high + 1)? < 0 {
+		high += 1;
+	}
+
+	Ok((high - low + 1) as u128)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn gcd_and_lcm() {
+		assert_eq!(gcd(12u32, 18), Ok(6));
+		assert_eq!(gcd(-12i64, 18), Ok(6));
+		assert_eq!(gcd(0u8, 0), Ok(0));
+		assert_eq!(gcd(i32::MIN, 2), Err(MathError::Overflow));
+		assert_eq!(lcm(4usize, 6), Ok(12));
+		assert_eq!(lcm_all(&[2u64, 3, 4, 5]), Ok(60));
+		assert_eq!(lcm_all::<u8>(&[]), Ok(1));
+		assert_eq!(lcm_all(&[200u8, 3]), Err(MathError::Overflow));
+		assert_eq!(gcd_all(&[24i32, -36, 60]), Ok(12));
+	}
+
+	#[test]
+	fn inverses() {
+		let (g, x, y) = extended_gcd(240, 46).unwrap();
+		assert_eq!(g, 2);
+		assert_eq!(240 * x + 46 * y, 2);
+		assert_eq!(mod_inverse(3, 11), Ok(4));
+		assert_eq!(mod_inverse(-3, 11), Ok(7));
+		assert_eq!(mod_inverse(4, 8), Err(MathError::NoSolution));
+		assert_eq!(mod_inverse(4, 0), Err(MathError::InvalidModulus));
+	}
+
+	#[test]
+	fn powers() {
+		assert_eq!(mod_pow(4, 13, 497), Ok(445));
+		assert_eq!(mod_pow(-2, 3, 5), Ok(2));
+		assert_eq!(mod_pow(7, 0, 1), Ok(0));
+		assert_eq!(
+			mod_pow(i128::MAX - 1, 2, i128::MAX),
+			Err(MathError::Overflow)
+		);
+	}
+
+	#[test]
+	fn chinese_remainders() {
+		assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Ok((23, 105)));
+		assert_eq!(crt(&[(3, 4), (5, 6)]), Ok((11, 12)));
+		assert_eq!(crt(&[(1, 4), (2, 6)]), Err(MathError::NoSolution));
+		assert_eq!(crt(&[]), Ok((0, 1)));
+	}
+
+	#[test]
+	fn roots_match_brute_force() {
+		for b in -40..40i128 {
+			for c in -40..40i128 {
+				let expected =
+					(-100..100).filter(|x| x * x + b * x + c < 0).count();
+				assert_eq!(count_between_roots(b, c), Ok(expected as u128));
+			}
+		}
+
+		// (x - 10)(x - 20): integer roots are excluded.
+		assert_eq!(count_between_roots(-30, 200), Ok(9));
+		assert_eq!(count_between_roots(i128::MAX, 0), Err(MathError::Overflow));
+	}
+}
@@ -0,0 +1,240 @@
+use std::{
+	collections::HashMap,
+	fmt::{self, Display},
+	hash::Hash,
+	marker::PhantomData,
+};
+
+/// Where a [`Memo`] keeps its answers.
+pub trait Cache<K, V> {
+	fn lookup(&self, key: &K) -> Option<&V>;
+	fn store(&mut self, key: K, value: V);
+	fn len(&self) -> usize;
+
+	fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+}
+
+impl<K: Hash + Eq, V> Cache<K, V> for HashMap<K, V> {
+	fn lookup(&self, key: &K) -> Option<&V> {
+		self.get(key)
+	}
+
+	fn store(&mut self, key: K, value: V) {
+		self.insert(key, value);
+	}
+
+	fn len(&self) -> usize {
+		HashMap::len(self)
+	}
+}
+
+/// A pre-sized table for keys that map onto small indices, which avoids
+/// hashing altogether. Panics on a key outside the table.
+pub struct Dense<K, V> {
+	cells: Vec<Option<V>>,
+	index: Box<dyn Fn(&K) -> usize>,
+	len: usize,
+}
+
+impl<K, V> Dense<K, V> {
+	pub fn new(size: usize, index: impl Fn(&K) -> usize + 'static) -> Self {
+		Self {
+			cells: std::iter::repeat_with(|| None).take(size).collect(),
+			index: Box::new(index),
+			len: 0,
+		}
+	}
+}
+
+impl<V> Dense<usize, V> {
+	pub fn linear(size: usize) -> Self {
+		Self::new(size, |&key| key)
+	}
+}
+
+impl<V> Dense<(usize, usize), V> {
+	/// A table for `(x, y)` keys with `x < width` and `y < height`.
+	pub fn grid(width: usize, height: usize) -> Self {
+		Self::new(width * height, move |&(x, y)| {
+			assert!(x < width && y < height, "Key out of table bounds");
+			y * width + x
+		})
+	}
+}
+
+impl<K, V> Cache<K, V> for Dense<K, V> {
+	fn lookup(&self, key: &K) -> Option<&V> {
+		self.cells[(self.index)(key)].as_ref()
+	}
+
+	fn store(&mut self, key: K, value: V) {
+		let cell = &mut self.cells[(self.index)(&key)];
+		if cell.is_none() {
+			self.len += 1;
+		}
+		*cell = Some(value);
+	}
+
+	fn len(&self) -> usize {
+		self.len
+	}
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+	pub hits: u64,
+	pub misses: u64,
+}
+
+impl Stats {
+	pub fn lookups(&self) -> u64 {
+		self.hits + self.misses
+	}
+
+	/// Fraction of lookups answered from the cache, 0 before any lookup.
+	pub fn hit_rate(&self) -> f64 {
+		match self.lookups() {
+			0 => 0.0,
+			lookups => self.hits as f64 / lookups as f64,
+		}
+	}
+}
+
+impl Display for Stats {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			f,
+			"{} hits, {} misses ({:.1}% hit rate)",
+			self.hits,
+			self.misses,
+			self.hit_rate() * 100.0
+		)
+	}
+}
+
+/// Caches the answers of a recursive function. The function body goes in
+/// the closure passed to [`Memo::get_or_compute`], which receives the memo
+/// back so it can recurse through it.
+pub struct Memo<K, V, C = HashMap<K, V>> {
+	cache: C,
+	stats: Stats,
+	entry: PhantomData<(K, V)>,
+}
+
+impl<K: Hash + Eq, V> Memo<K, V> {
+	pub fn new() -> Self {
+		Self::with_cache(HashMap::new())
+	}
+
+	pub fn with_capacity(capacity: usize) -> Self {
+		Self::with_cache(HashMap::with_capacity(capacity))
+	}
+}
+
+impl<K: Hash + Eq, V> Default for Memo<K, V> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<K, V, C: Cache<K, V>> Memo<K, V, C> {
+	pub fn with_cache(cache: C) -> Self {
+		Self {
+			cache,
+			stats: Stats::default(),
+			entry: PhantomData,
+		}
+	}
+
+	/// The cached answer for `key`, or else the result of `compute`, which
+	/// is cached before it is returned.
+	pub fn get_or_compute(
+		&mut self,
+		key: K,
+		compute: impl FnOnce(&mut Self) -> V,
+	) -> V
+	where
+		V: Clone,
+	{
+		if let Some(value) = self.cache.lookup(&key) {
+			self.stats.hits += 1;
+			return value.clone();
+		}
+
+		self.stats.misses += 1;
+		let value = compute(self);
+		self.cache.store(key, value.clone());
+		value
+	}
+
+	pub fn stats(&self) -> Stats {
+		self.stats
+	}
+
+	/// Number of cached answers.
+	pub fn len(&self) -> usize {
+		self.cache.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.cache.is_empty()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn paths<C: Cache<(usize, usize), u64>>(
+		memo: &mut Memo<(usize, usize), u64, C>,
+		x: usize,
+		y: usize,
+	) -> u64 {
+		memo.get_or_compute((x, y), |memo| match (x, y) {
+			(0, _) | (_, 0) => 1,
+			_ => paths(memo, x - 1, y) + paths(memo, x, y - 1),
+		})
+	}
+
+	#[test]
+	fn hashed_and_dense_agree() {
+		let mut hashed = Memo::new();
+		let mut dense = Memo::with_cache(Dense::grid(17, 17));
+
+		assert_eq!(paths(&mut hashed, 16, 16), 601080390);
+		assert_eq!(paths(&mut dense, 16, 16), 601080390);
+		assert_eq!(hashed.len(), 17 * 17 - 1);
+		assert_eq!(dense.len(), hashed.len());
+		assert_eq!(hashed.stats(), dense.stats());
+	}
+
+	#[test]
+	fn counts_hits_and_misses() {
+		let mut memo = Memo::with_cache(Dense::linear(4));
+		let mut calls = 0;
+
+		for key in [0, 1, 0, 3, 1, 0] {
+			memo.get_or_compute(key, |_| {
+				calls += 1;
+				key * 10
+			});
+		}
+
+		assert_eq!(calls, 3);
+		assert_eq!(memo.stats(), Stats { hits: 3, misses: 3 });
+		assert_eq!(memo.stats().hit_rate(), 0.5);
+		assert_eq!(
+			memo.stats().to_string(),
+			"3 hits, 3 misses (50.0% hit rate)"
+		);
+	}
+
+	#[test]
+	#[should_panic(expected = "Key out of table bounds")]
+	fn dense_bounds() {
+		let mut memo = Memo::with_cache(Dense::grid(2, 2));
+		memo.get_or_compute((2, 0), |_| 0);
+	}
+}
@@ -0,0 +1,632 @@
+use crate::{cli, grid::Grid};
+pub use aoc_derive::AocParse;
+use std::{
+	fmt::{self, Display},
+	str::FromStr,
+	sync::atomic::{AtomicU8, Ordering},
+};
+
+/// Where and why parsing failed. Lines and columns count from 1, with
+/// columns in bytes. Parsers that only see a single line report line 1;
+/// callers that know the real line fix it up with [`ParseError::on_line`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+	pub line: usize,
+	pub column: usize,
+	pub message: String,
+}
+
+impl ParseError {
+	pub fn new(line: usize, column: usize, message: impl Into<String>) -> Self {
+		Self {
+			line,
+			column,
+			message: message.into(),
+		}
+	}
+
+	pub fn on_line(self, line: usize) -> Self {
+		Self { line, ..self }
+	}
+}
+
+impl Display for ParseError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			f,
+			"line {}, column {}: {}",
+			self.line, self.column, self.message
+		)
+	}
+}
+
+impl std::error::Error for ParseError {}
+
+pub type Result<T> = std::result::Result<T, ParseError>;
+
+const UNSET: u8 = 0;
+const LENIENT: u8 = 1;
+const STRICT: u8 = 2;
+
+static MODE: AtomicU8 = AtomicU8::new(UNSET);
+
+/// Turns strict mode on or off, overriding the `--strict` flag.
+pub fn set_strict(strict: bool) {
+	MODE.store(if strict { STRICT } else { LENIENT }, Ordering::Relaxed);
+}
+
+/// Whether parsers reject malformed text they would otherwise skip over.
+/// Unless set, this is whether the program was run with `--strict`.
+pub fn is_strict() -> bool {
+	match MODE.load(Ordering::Relaxed) {
+		UNSET => {
+			let strict = cli::flag("strict");
+			set_strict(strict);
+			strict
+		}
+		mode => mode == STRICT,
+	}
+}
+
+/// Lets malformed text be skipped, unless in strict mode, where `error` is
+/// reported instead.
+pub fn skip(error: ParseError) -> Result<()> {
+	match is_strict() {
+		true => Err(error),
+		false => Ok(()),
+	}
+}
+
+/// Column of `part`, which must be a slice of `line`.
+pub fn column(line: &str, part: &str) -> usize {
+	part.as_ptr() as usize - line.as_ptr() as usize + 1
+}
+
+fn parse_at<T: FromStr>(line: &str, part: &str) -> Result<T>
+where
+	T::Err: Display,
+{
+	part.parse().map_err(|error| {
+		ParseError::new(
+			1,
+			column(line, part),
+			format!("Invalid value `{part}`: {error}"),
+		)
+	})
+}
+
+/// Every run of digits in `line[start..]`, with a directly preceding `-`
+/// as its sign when `signed` and the `-` doesn't follow a word. Anything
+/// else is skipped, including whole words with digits in them like `12x`,
+/// or when `strict` only whitespace is.
+fn scan<T: FromStr>(
+	line: &str,
+	start: usize,
+	signed: bool,
+	strict: bool,
+) -> Result<Vec<T>>
+where
+	T::Err: Display,
+{
+	let bytes = line.as_bytes();
+	let mut numbers = Vec::new();
+	let mut index = start;
+
+	while index < bytes.len() {
+		if !bytes[index].is_ascii_digit() {
+			let is_sign = signed
+				&& bytes[index] == b'-'
+				&& (index == 0 || bytes[index - 1].is_ascii_whitespace())
+				&& bytes.get(index + 1).is_some_and(u8::is_ascii_digit);
+			if strict && !bytes[index].is_ascii_whitespace() && !is_sign {
+				let found = line[index..].chars().next().unwrap_or_default();
+				return Err(ParseError::new(
+					1,
+					index + 1,
+					format!("Unexpected `{found}` between numbers"),
+				));
+			}
+			index += 1;
+			continue;
+		}
+
+		let mut begin = index;
+		while index < bytes.len() && bytes[index].is_ascii_digit() {
+			index += 1;
+		}
+
+		let in_word = (begin > 0 && bytes[begin - 1].is_ascii_alphabetic())
+			|| bytes.get(index).is_some_and(u8::is_ascii_alphabetic);
+		if !strict && in_word {
+			while index < bytes.len() && bytes[index].is_ascii_alphanumeric() {
+				index += 1;
+			}
+			continue;
+		}
+
+		let is_sign = |i: usize| {
+			bytes[i] == b'-'
+				&& (i == 0 || !bytes[i - 1].is_ascii_alphanumeric())
+		};
+		if signed && begin > start && is_sign(begin - 1) {
+			begin -= 1;
+		}
+
+		numbers.push(parse_at(line, &line[begin..index])?);
+	}
+
+	Ok(numbers)
+}
+
+/// Every integer in the line, with `-` signs.
+pub fn integers<T: FromStr>(line: &str) -> Result<Vec<T>>
+where
+	T::Err: Display,
+{
+	scan(line, 0, true, is_strict())
+}
+
+/// Every run of digits in the line, ignoring signs.
+pub fn unsigned_integers<T: FromStr>(line: &str) -> Result<Vec<T>>
+where
+	T::Err: Display,
+{
+	scan(line, 0, false, is_strict())
+}
+
+/// Splits `Label: values` into the trimmed label and values.
+pub fn labeled(line: &str) -> Result<(&str, &str)> {
+	line.split_once(':')
+		.map(|(label, values)| (label.trim(), values.trim()))
+		.ok_or_else(|| ParseError::new(1, 1, "Expected `Label: values`"))
+}
+
+/// The integers after `label:`, failing if the line has another label.
+pub fn labeled_integers<T: FromStr>(line: &str, label: &str) -> Result<Vec<T>>
+where
+	T::Err: Display,
+{
+	let (found, values) = labeled(line)?;
+	if found != label {
+		return Err(ParseError::new(
+			1,
+			column(line, found),
+			format!("Expected label `{label}`, found `{found}`"),
+		));
+	}
+
+	scan(line, column(line, values) - 1, true, is_strict())
+}
+
+/// Splits `key = (a, b)` into its trimmed key and pair.
+pub fn key_pair(line: &str) -> Result<(&str, (&str, &str))> {
+	let expected = "Expected `key = (a, b)`";
+
+	let (key, value) = line
+		.split_once('=')
+		.ok_or_else(|| ParseError::new(1, 1, expected))?;
+	let value = value.trim();
+
+	let inner = value
+		.strip_prefix('(')
+		.and_then(|value| value.strip_suffix(')'))
+		.ok_or_else(|| ParseError::new(1, column(line, value), expected))?;
+	let (a, b) = inner
+		.split_once(',')
+		.ok_or_else(|| ParseError::new(1, column(line, inner), expected))?;
+
+	Ok((key.trim(), (a.trim(), b.trim())))
+}
+
+/// Parses every comma separated, trimmed item.
+pub fn comma_list<T: FromStr>(text: &str) -> Result<Vec<T>>
+where
+	T::Err: Display,
+{
+	let mut items = Vec::new();
+	comma_list_into(text, &mut items)?;
+	Ok(items)
+}
+
+/// Like [`comma_list`], but replaces the contents of `items`, so a buffer
+/// can be reused from line to line.
+pub fn comma_list_into<T: FromStr>(text: &str, items: &mut Vec<T>) -> Result<()>
+where
+	T::Err: Display,
+{
+	items.clear();
+	for item in text.split(',') {
+		items.push(parse_at(text, item.trim())?);
+	}
+	Ok(())
+}
+
+/// Lines numbered from 1.
+pub fn lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+	input
+		.lines()
+		.enumerate()
+		.map(|(index, line)| (index + 1, line))
+}
+
+/// Parses every line, fixing up the line numbers of any error.
+pub fn each_line<T>(
+	input: &str,
+	mut parse: impl FnMut(&str) -> Result<T>,
+) -> Result<Vec<T>> {
+	lines(input)
+		.map(|(number, line)| parse(line).map_err(|e| e.on_line(number)))
+		.collect()
+}
+
+/// A run of lines between blank lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Section<'a> {
+	/// Line number of the first line.
+	pub line: usize,
+	pub text: &'a str,
+}
+
+impl<'a> Section<'a> {
+	/// The section's lines, numbered as in the whole input.
+	pub fn lines(&self) -> impl Iterator<Item = (usize, &'a str)> + 'a {
+		let first = self.line;
+		self.text
+			.lines()
+			.enumerate()
+			.map(move |(index, line)| (first + index, line))
+	}
+}
+
+/// Splits the input at blank lines, skipping any run of them.
+pub fn sections(input: &str) -> Vec<Section<'_>> {
+	let mut sections = Vec::new();
+	// First line number, start and end offsets of the open section.
+	let mut current: Option<(usize, usize, usize)> = None;
+
+	for (number, line) in lines(input) {
+		let begin = column(input, line) - 1;
+
+		if !line.trim().is_empty() {
+			let end = begin + line.len();
+			current = Some(match current {
+				Some((first, begin, _)) => (first, begin, end),
+				None => (number, begin, end),
+			});
+		} else if let Some((line, begin, end)) = current.take() {
+			sections.push(Section {
+				line,
+				text: &input[begin..end],
+			});
+		}
+	}
+
+	if let Some((line, begin, end)) = current {
+		sections.push(Section {
+			line,
+			text: &input[begin..end],
+		});
+	}
+
+	sections
+}
+
+/// A rectangular grid of bytes, failing on the first ragged row.
+pub fn char_grid(input: &str) -> Result<Grid<u8>> {
+	let mut width = None;
+	let mut cells = Vec::with_capacity(input.len());
+
+	for (number, line) in lines(input) {
+		match width {
+			None => width = Some(line.len()),
+			Some(width) if width != line.len() => {
+				return Err(ParseError::new(
+					number,
+					width.min(line.len()) + 1,
+					format!("Row has width {}, expected {}", line.len(), width),
+				));
+			}
+			_ => {}
+		}
+
+		cells.extend_from_slice(line.as_bytes());
+	}
+
+	let width = width.unwrap_or(0);
+	let height = cells.len().checked_div(width).unwrap_or(0);
+
+	Ok(Grid::from_cells(width, height, cells))
+}
+
+/// Helpers for the code `#[derive(AocParse)]` generates. Each takes the
+/// whole line so errors can point at the right column.
+#[doc(hidden)]
+pub mod derive {
+	use super::{column, parse_at, ParseError, Result};
+	use std::{fmt::Display, str::FromStr};
+
+	/// `rest` after the `literal` it must start with.
+	pub fn literal<'a>(
+		line: &str,
+		rest: &'a str,
+		literal: &str,
+	) -> Result<&'a str> {
+		rest.strip_prefix(literal).ok_or_else(|| {
+			ParseError::new(
+				1,
+				column(line, rest),
+				format!("Expected `{literal}`, found `{rest}`"),
+			)
+		})
+	}
+
+	/// Splits `rest` at the next `literal`, which stays at the start of the
+	/// second half. Without one, the error points at the end of the line,
+	/// where the literal was still expected.
+	pub fn until<'a>(
+		line: &str,
+		rest: &'a str,
+		literal: &str,
+	) -> Result<(&'a str, &'a str)> {
+		let index = rest.find(literal).ok_or_else(|| {
+			ParseError::new(
+				1,
+				column(line, &rest[rest.len()..]),
+				format!("Expected `{literal}`"),
+			)
+		})?;
+		Ok(rest.split_at(index))
+	}
+
+	pub fn field<T: FromStr>(line: &str, text: &str, name: &str) -> Result<T>
+	where
+		T::Err: Display,
+	{
+		parse_at(line, text).map_err(|error| ParseError {
+			message: format!("Invalid {name}: {}", error.message),
+			..error
+		})
+	}
+
+	pub fn list<T: FromStr>(
+		line: &str,
+		text: &str,
+		separator: &str,
+		name: &str,
+	) -> Result<Vec<T>>
+	where
+		T::Err: Display,
+	{
+		text.split(separator)
+			.map(|item| field(line, item, name))
+			.collect()
+	}
+
+	/// Fails if anything is left over after the format.
+	pub fn end(line: &str, rest: &str) -> Result<()> {
+		match rest {
+			"" => Ok(()),
+			_ => Err(ParseError::new(
+				1,
+				column(line, rest),
+				format!("Unexpected trailing `{rest}`"),
+			)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn numbers() {
+		assert_eq!(
+			integers::<i64>("x=-3, y=12 to 5-2"),
+			Ok(vec![-3, 12, 5, 2])
+		);
+		assert_eq!(
+			unsigned_integers::<u32>("Card  -4: 1 2"),
+			Ok(vec![4, 1, 2])
+		);
+		assert_eq!(integers::<i32>("a-1 b"), Ok(vec![1]));
+
+		let error = integers::<u8>("1 2 300").unwrap_err();
+		assert_eq!((error.line, error.column), (1, 5));
+	}
+
+	#[test]
+	fn strict_numbers() {
+		assert_eq!(
+			scan::<i64>(" -3  12\t5 ", 0, true, true),
+			Ok(vec![-3, 12, 5])
+		);
+		assert_eq!(
+			scan::<i64>("Time: 7 15 x", 5, true, true),
+			Err(ParseError::new(1, 12, "Unexpected `x` between numbers"))
+		);
+		assert_eq!(scan::<i64>("5-2", 0, true, true).unwrap_err().column, 2);
+		assert_eq!(scan::<u32>("1 -2", 0, false, true).unwrap_err().column, 3);
+		assert_eq!(scan::<u32>("1, 2", 0, false, false), Ok(vec![1, 2]));
+	}
+
+	#[test]
+	fn lenient_numbers() {
+		assert_eq!(
+			scan::<i64>("12x 3 x4 5a6 -7,8", 0, true, false),
+			Ok(vec![3, -7, 8])
+		);
+		assert_eq!(
+			scan::<i64>("12x 3", 0, true, true),
+			Err(ParseError::new(1, 3, "Unexpected `x` between numbers"))
+		);
+	}
+
+	#[test]
+	fn labels() {
+		assert_eq!(labeled("Time:  7 15"), Ok(("Time", "7 15")));
+		assert_eq!(
+			labeled_integers::<u64>("Distance: 9 40 200", "Distance"),
+			Ok(vec![9, 40, 200])
+		);
+		assert_eq!(
+			labeled_integers::<u64>("Time: 7", "Distance").unwrap_err(),
+			ParseError::new(1, 1, "Expected label `Distance`, found `Time`")
+		);
+		assert_eq!(labeled_integers::<u8>("seeds:", "seeds"), Ok(vec![]));
+	}
+
+	#[test]
+	fn pairs_and_lists() {
+		assert_eq!(key_pair("AAA = (BBB, CCC)"), Ok(("AAA", ("BBB", "CCC"))));
+		assert_eq!(key_pair("AAA = BBB, CCC").unwrap_err().column, 7);
+		assert_eq!(key_pair("AAA = (BBB CCC)").unwrap_err().column, 8);
+		assert_eq!(comma_list::<u8>("1,1, 3"), Ok(vec![1, 1, 3]));
+		assert_eq!(comma_list::<u8>("1,x").unwrap_err().column, 3);
+	}
+
+	#[test]
+	fn sections_and_lines() {
+		let input = "seeds: 1 2\n\n\nmap:\n1 2 3\n4 5 6\n\nlast\n";
+		let sections = sections(input);
+
+		assert_eq!(
+			sections,
+			vec![
+				Section {
+					line: 1,
+					text: "seeds: 1 2"
+				},
+				Section {
+					line: 4,
+					text: "map:\n1 2 3\n4 5 6"
+				},
+				Section {
+					line: 8,
+					text: "last"
+				},
+			]
+		);
+		assert_eq!(sections[1].lines().last(), Some((6, "4 5 6")));
+
+		let error = each_line("1,2\n3,x\n", comma_list::<u8>).unwrap_err();
+		assert_eq!((error.line, error.column), (2, 3));
+	}
+
+	#[test]
+	fn grids() {
+		let grid = char_grid("ab\ncd\nef\n").unwrap();
+		assert_eq!((grid.width(), grid.height()), (2, 3));
+		assert_eq!(grid[(1, 2)], b'f');
+
+		let error = char_grid("abc\nab\n").unwrap_err();
+		assert_eq!((error.line, error.column), (2, 3));
+	}
+
+	#[derive(Debug, PartialEq, Eq, AocParse)]
+	#[aoc(format = "{node} = ({left}, {right})")]
+	struct Element {
+		node: String,
+		left: String,
+		right: String,
+	}
+
+	#[derive(Debug, PartialEq, Eq, AocParse)]
+	#[aoc(format = "Game {id}: {_} {cubes}")]
+	struct Game {
+		id: u32,
+		#[aoc(separator = ", ")]
+		cubes: Vec<u32>,
+	}
+
+	#[derive(Debug, PartialEq, Eq, AocParse)]
+	#[aoc(format = "{name} ({weight}) -> {children}")]
+	struct Program<'a> {
+		name: &'a str,
+		weight: u32,
+		#[aoc(separator = ", ")]
+		children: Vec<&'a str>,
+	}
+
+	#[test]
+	fn derived() {
+		assert_eq!(
+			"AAA = (BBB, CCC)".parse(),
+			Ok(Element {
+				node: "AAA".into(),
+				left: "BBB".into(),
+				right: "CCC".into(),
+			})
+		);
+		assert_eq!(
+			"Game 12: cubes 1, 2, 3".parse(),
+			Ok(Game {
+				id: 12,
+				cubes: vec![1, 2, 3],
+			})
+		);
+
+		for (line, column) in [
+			("AAA = BBB, CCC)", 16),
+			("AAA = (BBB CCC)", 16),
+			("AAA = (BBB, CCC))", 17),
+		] {
+			let error = line.parse::<Element>().unwrap_err();
+			assert_eq!(error.column, column, "{line}: {error}");
+		}
+
+		let error = "Game 1: x 2, x".parse::<Game>().unwrap_err();
+		assert_eq!(error.column, 14);
+		assert!(error.message.starts_with("Invalid cubes"), "{error}");
+
+		let line = String::from("fwft (72) -> ktlj, cntj");
+		let program = Program::try_from(line.as_str()).unwrap();
+		assert_eq!(program.weight, 72);
+		assert_eq!(program.children, ["ktlj", "cntj"]);
+		assert!(std::ptr::eq(program.name, &line[..4]));
+
+		let error = Program::try_from("fwft (x) -> a").unwrap_err();
+		assert_eq!(error.column, 7);
+
+		let error = Program::try_from("fwft (72)").unwrap_err();
+		assert_eq!(
+			(error.column, error.message.as_str()),
+			(10, "Expected `) -> `")
+		);
+	}
+
+	#[test]
+	fn borrowed_fields() {
+		// With a lifetime the derive gives `TryFrom`, not `FromStr`.
+		fn borrows<'a, T: TryFrom<&'a str, Error = ParseError>>() {}
+		borrows::<Program>();
+
+		let program = Program::try_from("abc (1) -> d").unwrap();
+		assert_eq!((program.name, program.children), ("abc", vec!["d"]));
+	}
+
+	#[test]
+	fn fields_named_like_locals() {
+		#[derive(Debug, PartialEq, Eq, AocParse)]
+		#[aoc(format = "{line}: {text} | {rest}")]
+		struct Locals {
+			line: u32,
+			text: String,
+			rest: String,
+		}
+
+		assert_eq!(
+			"3: a b | c".parse(),
+			Ok(Locals {
+				line: 3,
+				text: "a b".into(),
+				rest: "c".into(),
+			})
+		);
+		let error = "3: a b".parse::<Locals>().unwrap_err();
+		assert_eq!(
+			(error.column, error.message.as_str()),
+			(7, "Expected ` | `")
+		);
+	}
+}
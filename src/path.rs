@@ -0,0 +1,384 @@
+use std::{
+	cmp::Reverse,
+	collections::{BinaryHeap, HashMap, VecDeque},
+	hash::Hash,
+	ops::Add,
+};
+
+/// Edge weights usable by [`dijkstra`] and [`astar`]. `Default` is zero.
+pub trait Cost: Copy + Ord + Default + Add<Output = Self> {}
+
+impl<C: Copy + Ord + Default + Add<Output = C>> Cost for C {}
+
+/// A shortest path: its total cost and every state from source to target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<S, C> {
+	pub cost: C,
+	pub states: Vec<S>,
+}
+
+/// Result of a search: the settled distances of every state it reached, the
+/// tree to reconstruct paths from, and the first target reached, if any.
+#[derive(Debug, Clone)]
+pub struct Search<S, C> {
+	index: HashMap<S, usize>,
+	states: Vec<S>,
+	costs: Vec<C>,
+	parents: Vec<Option<usize>>,
+	settled: Vec<bool>,
+	target: Option<usize>,
+}
+
+impl<S: Clone + Eq + Hash, C: Cost> Search<S, C> {
+	fn new() -> Self {
+		Self {
+			index: HashMap::new(),
+			states: Vec::new(),
+			costs: Vec::new(),
+			parents: Vec::new(),
+			settled: Vec::new(),
+			target: None,
+		}
+	}
+
+	/// Records `state` at `cost` if it is new or cheaper than before,
+	/// returning its index.
+	fn relax(
+		&mut self,
+		state: S,
+		cost: C,
+		parent: Option<usize>,
+	) -> Option<usize> {
+		match self.index.get(&state) {
+			Some(&index)
+				if self.settled[index] || self.costs[index] <= cost =>
+			{
+				None
+			}
+			Some(&index) => {
+				self.costs[index] = cost;
+				self.parents[index] = parent;
+				Some(index)
+			}
+			None => {
+				let index = self.states.len();
+				self.index.insert(state.clone(), index);
+				self.states.push(state);
+				self.costs.push(cost);
+				self.parents.push(parent);
+				self.settled.push(false);
+				Some(index)
+			}
+		}
+	}
+
+	/// The first target state the search settled.
+	pub fn target(&self) -> Option<&S> {
+		self.target.map(|index| &self.states[index])
+	}
+
+	/// Shortest distance to `state`, if the search settled it.
+	pub fn cost(&self, state: &S) -> Option<C> {
+		let &index = self.index.get(state)?;
+		self.settled[index].then_some(self.costs[index])
+	}
+
+	/// Every settled state and its shortest distance.
+	pub fn costs(&self) -> impl Iterator<Item = (&S, C)> {
+		self.states
+			.iter()
+			.zip(&self.costs)
+			.zip(&self.settled)
+			.filter_map(|((state, &cost), &settled)| {
+				settled.then_some((state, cost))
+			})
+	}
+
+	/// Shortest path from the nearest source to `state`.
+	pub fn path_to(&self, state: &S) -> Option<Path<S, C>> {
+		let &index = self.index.get(state)?;
+		self.settled[index].then(|| self.path_from_index(index))
+	}
+
+	/// Shortest path to the target, if one was reached.
+	pub fn path(&self) -> Option<Path<S, C>> {
+		self.target.map(|index| self.path_from_index(index))
+	}
+
+	fn path_from_index(&self, index: usize) -> Path<S, C> {
+		let mut states: Vec<S> =
+			std::iter::successors(Some(index), |&i| self.parents[i])
+				.map(|i| self.states[i].clone())
+				.collect();
+		states.reverse();
+
+		Path {
+			cost: self.costs[index],
+			states,
+		}
+	}
+}
+
+/// Breadth first search where every step costs one. Stops at the first
+/// state matching `is_target`, or explores everything reachable if none
+/// does.
+pub fn bfs<S, I>(
+	starts: impl IntoIterator<Item = S>,
+	mut neighbors: impl FnMut(&S) -> I,
+	is_target: impl Fn(&S) -> bool,
+) -> Search<S, usize>
+where
+	S: Clone + Eq + Hash,
+	I: IntoIterator<Item = S>,
+{
+	let mut search = Search::new();
+	let mut queue = VecDeque::new();
+
+	for start in starts {
+		if let Some(index) = search.relax(start, 0, None) {
+			search.settled[index] = true;
+			queue.push_back(index);
+		}
+	}
+
+	while let Some(index) = queue.pop_front() {
+		if is_target(&search.states[index]) {
+			search.target = Some(index);
+			break;
+		}
+
+		let cost = search.costs[index] + 1;
+		for next in neighbors(&search.states[index].clone()) {
+			if let Some(next) = search.relax(next, cost, Some(index)) {
+				search.settled[next] = true;
+				queue.push_back(next);
+			}
+		}
+	}
+
+	search
+}
+
+/// Dijkstra's algorithm over non-negative edge costs.
+pub fn dijkstra<S, C, I>(
+	starts: impl IntoIterator<Item = S>,
+	neighbors: impl FnMut(&S) -> I,
+	is_target: impl Fn(&S) -> bool,
+) -> Search<S, C>
+where
+	S: Clone + Eq + Hash,
+	C: Cost,
+	I: IntoIterator<Item = (S, C)>,
+{
+	astar(starts, neighbors, |_| C::default(), is_target)
+}
+
+/// A* search. `heuristic` must never overestimate the remaining cost to the
+/// nearest target, and should be consistent for the search to settle every
+/// state only once.
+pub fn astar<S, C, I>(
+	starts: impl IntoIterator<Item = S>,
+	mut neighbors: impl FnMut(&S) -> I,
+	heuristic: impl Fn(&S) -> C,
+	is_target: impl Fn(&S) -> bool,
+) -> Search<S, C>
+where
+	S: Clone + Eq + Hash,
+	C: Cost,
+	I: IntoIterator<Item = (S, C)>,
+{
+	let mut search = Search::new();
+	let mut heap = BinaryHeap::new();
+
+	for start in starts {
+		if let Some(index) = search.relax(start, C::default(), None) {
+			let priority = heuristic(&search.states[index]);
+			heap.push(Reverse((priority, index)));
+		}
+	}
+
+	while let Some(Reverse((_, index))) = heap.pop() {
+		if search.settled[index] {
+			continue;
+		}
+		search.settled[index] = true;
+
+		if is_target(&search.states[index]) {
+			search.target = Some(index);
+			break;
+		}
+
+		let cost = search.costs[index];
+		for (next, step) in neighbors(&search.states[index].clone()) {
+			let next_cost = cost + step;
+			let priority = next_cost + heuristic(&next);
+
+			if let Some(next) = search.relax(next, next_cost, Some(index)) {
+				heap.push(Reverse((priority, next)));
+			}
+		}
+	}
+
+	search
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		graph::Graph,
+		grid::{Direction, Grid},
+	};
+
+	const MAZE: &[u8] = b"\
+S.#.....
+.##.###.
+....#...
+.##...#T
+...##...";
+
+	fn open(grid: &Grid<u8>, position: (usize, usize)) -> Vec<(usize, usize)> {
+		grid.neighbors(position)
+			.filter(|&next| grid[next] != b'#')
+			.collect()
+	}
+
+	#[test]
+	fn grid_bfs() {
+		let grid = Grid::from_bytes(MAZE).unwrap();
+		let search = bfs([(0, 0)], |&p| open(&grid, p), |&p| grid[p] == b'T');
+
+		let path = search.path().unwrap();
+		assert_eq!(path.cost, 12);
+		assert_eq!(path.states.len(), 13);
+		assert_eq!(path.states.first(), Some(&(0, 0)));
+		assert_eq!(search.target(), Some(&(7, 3)));
+		assert!(path.states.windows(2).all(|w| {
+			w[0].0.abs_diff(w[1].0) + w[0].1.abs_diff(w[1].1) == 1
+		}));
+	}
+
+	#[test]
+	fn astar_matches_dijkstra() {
+		let grid = Grid::from_bytes(MAZE).unwrap();
+		let weighted = |&p: &(usize, usize)| {
+			open(&grid, p).into_iter().map(|next| (next, 1 + next.1))
+		};
+		let is_target = |p: &(usize, usize)| *p == (7, 3);
+
+		let plain = dijkstra([(0, 0), (7, 0)], weighted, is_target);
+		let guided = astar(
+			[(0, 0), (7, 0)],
+			weighted,
+			|&(x, y)| x.abs_diff(7) + y.abs_diff(3),
+			is_target,
+		);
+
+		assert_eq!(plain.path().unwrap().cost, 9);
+		assert_eq!(guided.path(), plain.path());
+		assert_eq!(plain.path().unwrap().states[0], (7, 0));
+	}
+
+	#[test]
+	fn graph_distances() {
+		let mut graph = Graph::new();
+		graph.add_edge("a", 7, "b");
+		graph.add_edge("a", 2, "c");
+		graph.add_edge("c", 3, "b");
+		graph.add_edge("b", 1, "d");
+
+		let a = graph.id("a").unwrap();
+		let search = dijkstra(
+			[a],
+			|&node| graph.neighbors(node).map(|(&w, to)| (to, w)),
+			|_| false,
+		);
+
+		let d = graph.id("d").unwrap();
+		let path = search.path_to(&d).unwrap();
+		assert_eq!(path.cost, 6);
+		let names: Vec<_> =
+			path.states.iter().map(|&id| graph.name(id)).collect();
+		assert_eq!(names, ["a", "c", "b", "d"]);
+		assert_eq!(search.costs().count(), 4);
+		assert_eq!(search.target(), None);
+	}
+
+	#[test]
+	fn custom_state() {
+		// At most `limit` steps in a straight line before turning.
+		#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+		struct State {
+			position: (usize, usize),
+			direction: Direction,
+			run: u8,
+		}
+
+		fn state(x: usize, y: usize, direction: Direction, run: u8) -> State {
+			State {
+				position: (x, y),
+				direction,
+				run,
+			}
+		}
+
+		fn search(
+			grid: &Grid<u32>,
+			target: (usize, usize),
+			limit: u8,
+		) -> Search<State, u32> {
+			dijkstra(
+				[state(0, 0, Direction::East, 0)],
+				|state: &State| {
+					Direction::ALL
+						.into_iter()
+						.filter(|&d| d != state.direction.opposite())
+						.filter_map(|direction| {
+							let run = match direction == state.direction {
+								true => state.run + 1,
+								false => 1,
+							};
+							let position =
+								grid.step(state.position, direction)?;
+							(run <= limit).then_some((
+								State {
+									position,
+									direction,
+									run,
+								},
+								grid[position],
+							))
+						})
+						.collect::<Vec<_>>()
+				},
+				|state| state.position == target,
+			)
+		}
+
+		// A single row leaves no room to turn after three steps.
+		let row = Grid::new(6, 1, 1u32);
+		let search_row = search(&row, (5, 0), 3);
+		assert!(search_row.path().is_none());
+		assert!(search_row.costs().all(|(state, _)| state.position.0 <= 3));
+
+		// The cheap top row is one step too long, so the path has to dip into
+		// the expensive bottom row, and does so where it is cheapest.
+		let grid = Grid::from_cells(5, 2, vec![1, 1, 1, 1, 1, 9, 9, 9, 9, 1]);
+		assert_eq!(search(&grid, (4, 0), 4).path().unwrap().cost, 4);
+
+		let path = search(&grid, (4, 0), 3).path().unwrap();
+		assert_eq!(path.cost, 14);
+		assert_eq!(
+			path.states,
+			[
+				state(0, 0, Direction::East, 0),
+				state(1, 0, Direction::East, 1),
+				state(2, 0, Direction::East, 2),
+				state(3, 0, Direction::East, 3),
+				state(3, 1, Direction::South, 1),
+				state(4, 1, Direction::East, 1),
+				state(4, 0, Direction::North, 1),
+			]
+		);
+	}
+}
@@ -0,0 +1,220 @@
+use crate::range::RangeSet;
+use std::ops::Range;
+
+/// A function on integers that adds a constant offset on each of a set of
+/// disjoint source ranges and is the identity everywhere else, as in the
+/// almanac maps of day 5.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PiecewiseMap {
+	/// Sorted, disjoint source ranges and their offsets. Never holds a zero
+	/// offset or two touching pieces with the same offset.
+	pieces: Vec<(Range<i64>, i64)>,
+}
+
+impl PiecewiseMap {
+	pub fn identity() -> Self {
+		Self::default()
+	}
+
+	/// Builds the map from `(destination, source, length)` triples. Where
+	/// source ranges overlap, the earlier triple wins.
+	pub fn from_triples(
+		triples: impl IntoIterator<Item = (i64, i64, i64)>,
+	) -> Self {
+		let mut covered = RangeSet::new();
+		let mut pieces = Vec::new();
+
+		for (destination, source, length) in triples {
+			let range = RangeSet::from(source..source + length);
+
+			for piece in range.difference(&covered).ranges() {
+				pieces.push((piece.clone(), destination - source));
+			}
+
+			covered = covered.union(&range);
+		}
+
+		Self::normalized(pieces)
+	}
+
+	fn normalized(mut pieces: Vec<(Range<i64>, i64)>) -> Self {
+		pieces
+			.retain(|(range, offset)| range.start < range.end && *offset != 0);
+		pieces.sort_unstable_by_key(|(range, _)| range.start);
+
+		let mut merged: Vec<(Range<i64>, i64)> =
+			Vec::with_capacity(pieces.len());
+		for (range, offset) in pieces {
+			match merged.last_mut() {
+				Some((last, last_offset))
+					if last.end == range.start && *last_offset == offset =>
+				{
+					last.end = range.end;
+				}
+				_ => merged.push((range, offset)),
+			}
+		}
+
+		Self { pieces: merged }
+	}
+
+	pub fn pieces(&self) -> &[(Range<i64>, i64)] {
+		&self.pieces
+	}
+
+	pub fn get(&self, value: i64) -> i64 {
+		let index =
+			self.pieces.partition_point(|(range, _)| range.end <= value);
+
+		match self.pieces.get(index) {
+			Some((range, offset)) if range.start <= value => value + offset,
+			_ => value,
+		}
+	}
+
+	/// Every maximal range the map shifts by a single offset, covering all
+	/// of `i64` including the identity gaps between pieces.
+	fn segments(&self) -> impl Iterator<Item = (Range<i64>, i64)> + '_ {
+		let mut start = i64::MIN;
+		let mut pieces = self.pieces.iter();
+		let mut pending: Option<(Range<i64>, i64)> = None;
+
+		std::iter::from_fn(move || {
+			if let Some(piece) = pending.take() {
+				start = piece.0.end;
+				return Some(piece);
+			}
+
+			match pieces.next() {
+				Some((range, offset)) if range.start > start => {
+					pending = Some((range.clone(), *offset));
+					let gap = start..range.start;
+					start = range.start;
+					Some((gap, 0))
+				}
+				Some((range, offset)) => {
+					start = range.end;
+					Some((range.clone(), *offset))
+				}
+				None if start < i64::MAX => {
+					let gap = start..i64::MAX;
+					start = i64::MAX;
+					Some((gap, 0))
+				}
+				None => None,
+			}
+		})
+	}
+
+	/// `x ↦ then(self(x))` as a single map.
+	pub fn compose(&self, then: &PiecewiseMap) -> PiecewiseMap {
+		let cuts: Vec<i64> = then
+			.pieces
+			.iter()
+			.flat_map(|(range, _)| [range.start, range.end])
+			.collect();
+
+		let mut pieces = Vec::new();
+
+		for (range, offset) in self.segments() {
+			let image = RangeSet::from(range).shift_up(offset);
+
+			for piece in image.split(cuts.iter().copied()) {
+				let total = offset + (then.get(piece.start) - piece.start);
+				pieces.push((piece.start - offset..piece.end - offset, total));
+			}
+		}
+
+		Self::normalized(pieces)
+	}
+
+	/// Every value the map sends `set` to.
+	pub fn image(&self, set: &RangeSet<i64>) -> RangeSet<i64> {
+		self.segments()
+			.flat_map(|(range, offset)| {
+				set.intersection(&RangeSet::from(range))
+					.shift_up(offset)
+					.ranges()
+					.to_vec()
+			})
+			.collect()
+	}
+
+	/// Every value the map sends to `value`, in increasing order.
+	pub fn preimage(&self, value: i64) -> Vec<i64> {
+		let mut values: Vec<i64> = self
+			.pieces
+			.iter()
+			.map(|(range, offset)| (range, value - offset))
+			.filter(|(range, source)| range.contains(source))
+			.map(|(_, source)| source)
+			.collect();
+
+		if self.get(value) == value {
+			values.push(value);
+		}
+
+		values.sort_unstable();
+		values
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn seed_to_soil() -> PiecewiseMap {
+		PiecewiseMap::from_triples([(50, 98, 2), (52, 50, 48)])
+	}
+
+	fn soil_to_fertilizer() -> PiecewiseMap {
+		PiecewiseMap::from_triples([(0, 15, 37), (37, 52, 2), (39, 0, 15)])
+	}
+
+	#[test]
+	fn lookups() {
+		let map = seed_to_soil();
+		assert_eq!(map.get(79), 81);
+		assert_eq!(map.get(98), 50);
+		assert_eq!(map.get(10), 10);
+		assert_eq!(map.preimage(51), vec![99]);
+		assert_eq!(map.preimage(50), vec![98]);
+		assert_eq!(map.preimage(10), vec![10]);
+		assert_eq!(map.preimage(99), vec![97]);
+	}
+
+	#[test]
+	fn earlier_triples_win() {
+		let map = PiecewiseMap::from_triples([(100, 0, 10), (200, 5, 10)]);
+		assert_eq!(map.get(7), 107);
+		assert_eq!(map.get(12), 207);
+	}
+
+	#[test]
+	fn composition_matches_pointwise() {
+		let (first, second) = (seed_to_soil(), soil_to_fertilizer());
+		let composed = first.compose(&second);
+
+		for x in -5..120 {
+			assert_eq!(composed.get(x), second.get(first.get(x)), "{x}");
+		}
+		assert_eq!(
+			PiecewiseMap::identity().compose(&first),
+			first.compose(&PiecewiseMap::identity())
+		);
+	}
+
+	#[test]
+	fn images() {
+		let map = seed_to_soil().compose(&soil_to_fertilizer());
+		let seeds: RangeSet<i64> = [79..93, 55..68].into_iter().collect();
+		let image = map.image(&seeds);
+
+		let mut expected: Vec<i64> =
+			(55..68).chain(79..93).map(|x| map.get(x)).collect();
+		expected.sort_unstable();
+		let actual: Vec<i64> =
+			image.ranges().iter().flat_map(|r| r.clone()).collect();
+		assert_eq!(actual, expected);
+	}
+}
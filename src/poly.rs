@@ -0,0 +1,322 @@
+use crate::math::gcd;
+use std::fmt::{self, Display};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolyError {
+	/// There are no values to fit.
+	Empty,
+	/// No row of differences becomes all zeroes within the sequence.
+	NotPolynomial,
+	/// An intermediate or final value doesn't fit in an `i128`.
+	Overflow,
+}
+
+impl Display for PolyError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			PolyError::Empty => write!(f, "Empty sequence"),
+			PolyError::NotPolynomial => {
+				write!(f, "Sequence is not polynomial within its length")
+			}
+			PolyError::Overflow => write!(f, "Arithmetic overflow"),
+		}
+	}
+}
+
+impl std::error::Error for PolyError {}
+
+pub type Result<T> = std::result::Result<T, PolyError>;
+
+/// An exact fraction, always in lowest terms with a positive denominator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+	numerator: i128,
+	denominator: i128,
+}
+
+impl Rational {
+	pub const ZERO: Rational = Rational {
+		numerator: 0,
+		denominator: 1,
+	};
+
+	pub const ONE: Rational = Rational {
+		numerator: 1,
+		denominator: 1,
+	};
+
+	/// `None` if the denominator is zero or the fraction can't be reduced
+	/// without overflow.
+	pub fn new(numerator: i128, denominator: i128) -> Option<Rational> {
+		if denominator == 0 {
+			return None;
+		}
+
+		let g = gcd(numerator, denominator).ok()?;
+		let (numerator, denominator) = (numerator / g, denominator / g);
+
+		if denominator < 0 {
+			Some(Rational {
+				numerator: numerator.checked_neg()?,
+				denominator: denominator.checked_neg()?,
+			})
+		} else {
+			Some(Rational {
+				numerator,
+				denominator,
+			})
+		}
+	}
+
+	pub fn numerator(&self) -> i128 {
+		self.numerator
+	}
+
+	pub fn denominator(&self) -> i128 {
+		self.denominator
+	}
+
+	/// The value as an integer, if it is one.
+	pub fn to_integer(&self) -> Option<i128> {
+		(self.denominator == 1).then_some(self.numerator)
+	}
+
+	pub fn checked_add(self, other: Rational) -> Option<Rational> {
+		let g = gcd(self.denominator, other.denominator).ok()?;
+		let (a, b) = (self.denominator / g, other.denominator / g);
+
+		Rational::new(
+			self.numerator
+				.checked_mul(b)?
+				.checked_add(other.numerator.checked_mul(a)?)?,
+			self.denominator.checked_mul(b)?,
+		)
+	}
+
+	pub fn checked_sub(self, other: Rational) -> Option<Rational> {
+		self.checked_add(other.checked_neg()?)
+	}
+
+	pub fn checked_mul(self, other: Rational) -> Option<Rational> {
+		// Cross-reduce first so the products stay as small as possible.
+		let g1 = gcd(self.numerator, other.denominator).ok()?;
+		let g2 = gcd(other.numerator, self.denominator).ok()?;
+
+		Rational::new(
+			(self.numerator / g1).checked_mul(other.numerator / g2)?,
+			(self.denominator / g2).checked_mul(other.denominator / g1)?,
+		)
+	}
+
+	pub fn checked_div(self, other: Rational) -> Option<Rational> {
+		let inverse = Rational::new(other.denominator, other.numerator)?;
+		self.checked_mul(inverse)
+	}
+
+	pub fn checked_neg(self) -> Option<Rational> {
+		Some(Rational {
+			numerator: self.numerator.checked_neg()?,
+			denominator: self.denominator,
+		})
+	}
+}
+
+impl From<i128> for Rational {
+	fn from(value: i128) -> Self {
+		Rational {
+			numerator: value,
+			denominator: 1,
+		}
+	}
+}
+
+impl Display for Rational {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self.denominator {
+			1 => write!(f, "{}", self.numerator),
+			_ => write!(f, "{}/{}", self.numerator, self.denominator),
+		}
+	}
+}
+
+/// The polynomial through a sequence of values sampled at `x = 0, 1, 2, ...`,
+/// kept in Newton form as the forward differences at `x = 0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Newton {
+	differences: Vec<i128>,
+	len: usize,
+}
+
+impl Newton {
+	/// Fits the lowest degree polynomial through `values`. Fails unless some
+	/// row of differences is all zeroes before the rows run out, since
+	/// otherwise the sequence doesn't pin down a polynomial.
+	pub fn fit(values: &[i64]) -> Result<Newton> {
+		if values.is_empty() {
+			return Err(PolyError::Empty);
+		}
+
+		let mut row: Vec<i128> = values.iter().map(|&x| x as i128).collect();
+		let mut differences = Vec::new();
+
+		while !row.is_empty() {
+			if row.iter().all(|&x| x == 0) {
+				return Ok(Newton {
+					differences,
+					len: values.len(),
+				});
+			}
+
+			differences.push(row[0]);
+			row = row
+				.windows(2)
+				.map(|w| w[1].checked_sub(w[0]).ok_or(PolyError::Overflow))
+				.collect::<Result<_>>()?;
+		}
+
+		Err(PolyError::NotPolynomial)
+	}
+
+	/// Degree of the polynomial, with the zero polynomial counted as 0.
+	pub fn degree(&self) -> usize {
+		self.differences.len().saturating_sub(1)
+	}
+
+	/// Number of values the polynomial was fitted to.
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	/// Forward differences at `x = 0`, lowest order first.
+	pub fn differences(&self) -> &[i128] {
+		&self.differences
+	}
+
+	/// The value at an integer `x`, which may lie outside the sequence.
+	pub fn at(&self, x: i64) -> Result<i128> {
+		let x = x as i128;
+		let mut binomial: i128 = 1;
+		let mut total: i128 = 0;
+
+		for (k, &difference) in self.differences.iter().enumerate() {
+			if k > 0 {
+				// C(x, k) = C(x, k - 1) * (x - k + 1) / k, and the division
+				// is exact.
+				binomial = binomial
+					.checked_mul(x - k as i128 + 1)
+					.ok_or(PolyError::Overflow)?
+					/ k as i128;
+			}
+
+			total = binomial
+				.checked_mul(difference)
+				.and_then(|term| total.checked_add(term))
+				.ok_or(PolyError::Overflow)?;
+		}
+
+		Ok(total)
+	}
+
+	/// The value `steps` places after the last one.
+	pub fn forward(&self, steps: usize) -> Result<i128> {
+		let x = (self.len - 1)
+			.checked_add(steps)
+			.and_then(|x| i64::try_from(x).ok())
+			.ok_or(PolyError::Overflow)?;
+		self.at(x)
+	}
+
+	/// The value `steps` places before the first one.
+	pub fn backward(&self, steps: usize) -> Result<i128> {
+		let x = i64::try_from(steps).map_err(|_| PolyError::Overflow)?;
+		self.at(-x)
+	}
+
+	/// The value at any rational `x`.
+	pub fn evaluate(&self, x: Rational) -> Result<Rational> {
+		let mut binomial = Rational::ONE;
+		let mut total = Rational::ZERO;
+
+		for (k, &difference) in self.differences.iter().enumerate() {
+			if k > 0 {
+				let k = k as i128;
+				binomial = x
+					.checked_sub(Rational::from(k - 1))
+					.and_then(|factor| binomial.checked_mul(factor))
+					.and_then(|b| b.checked_div(Rational::from(k)))
+					.ok_or(PolyError::Overflow)?;
+			}
+
+			total = binomial
+				.checked_mul(Rational::from(difference))
+				.and_then(|term| total.checked_add(term))
+				.ok_or(PolyError::Overflow)?;
+		}
+
+		Ok(total)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn fits_degrees() {
+		let linear = Newton::fit(&[0, 3, 6, 9, 12, 15]).unwrap();
+		assert_eq!(linear.degree(), 1);
+		assert_eq!(linear.forward(1), Ok(18));
+		assert_eq!(linear.backward(1), Ok(-3));
+
+		let cubic: Vec<i64> = (0..8).map(|x| x * x * x - 2 * x + 5).collect();
+		let cubic = Newton::fit(&cubic).unwrap();
+		assert_eq!(cubic.degree(), 3);
+		for x in -20..20 {
+			assert_eq!(cubic.at(x), Ok((x * x * x - 2 * x + 5) as i128));
+		}
+
+		assert_eq!(Newton::fit(&[0, 0, 0]).unwrap().degree(), 0);
+		assert_eq!(Newton::fit(&[7, 7]).unwrap().at(100), Ok(7));
+	}
+
+	#[test]
+	fn day_9_example() {
+		let newton = Newton::fit(&[10, 13, 16, 21, 30, 45]).unwrap();
+		assert_eq!(newton.forward(1), Ok(68));
+		assert_eq!(newton.backward(1), Ok(5));
+		assert_eq!(newton.forward(3), newton.at(8));
+	}
+
+	#[test]
+	fn rejects_non_polynomial() {
+		assert_eq!(Newton::fit(&[]), Err(PolyError::Empty));
+		assert_eq!(Newton::fit(&[7]), Err(PolyError::NotPolynomial));
+		assert_eq!(
+			Newton::fit(&[1, 2, 4, 8, 16]),
+			Err(PolyError::NotPolynomial)
+		);
+		assert_eq!(
+			Newton::fit(&[i64::MIN, i64::MAX, i64::MIN, i64::MAX]),
+			Err(PolyError::NotPolynomial)
+		);
+	}
+
+	#[test]
+	fn rational_points() {
+		// x^2, sampled at 0..5, is fractional between the samples.
+		let newton = Newton::fit(&[0, 1, 4, 9, 16]).unwrap();
+		let half = Rational::new(1, 2).unwrap();
+		assert_eq!(newton.evaluate(half), Ok(Rational::new(1, 4).unwrap()));
+		assert_eq!(
+			newton.evaluate(Rational::new(-7, 3).unwrap()),
+			Ok(Rational::new(49, 9).unwrap())
+		);
+		assert_eq!(Rational::new(6, -4).unwrap().to_string(), "-3/2");
+		assert_eq!(Rational::new(0, 5), Some(Rational::ZERO));
+		assert_eq!(Rational::new(1, 0), None);
+	}
+}
@@ -0,0 +1,313 @@
+use std::{
+	fmt::Debug,
+	ops::{Add, Range, Sub},
+};
+
+/// Integer types a [`RangeSet`] can hold.
+pub trait Bound:
+	Copy + Ord + Debug + Add<Output = Self> + Sub<Output = Self>
+{
+	const ZERO: Self;
+	const ONE: Self;
+}
+
+macro_rules! impl_bound {
+	($($t:ty),*) => {
+		$(impl Bound for $t {
+			const ZERO: Self = 0;
+			const ONE: Self = 1;
+		})*
+	};
+}
+
+impl_bound!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// A set of integers stored as sorted, disjoint, non-adjacent half-open
+/// ranges.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RangeSet<T> {
+	ranges: Vec<Range<T>>,
+}
+
+impl<T: Bound> Default for RangeSet<T> {
+	fn default() -> Self {
+		Self { ranges: Vec::new() }
+	}
+}
+
+impl<T: Bound> RangeSet<T> {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	fn normalized(mut ranges: Vec<Range<T>>) -> Self {
+		ranges.retain(|range| range.start < range.end);
+		ranges.sort_unstable_by_key(|range| range.start);
+
+		let mut merged: Vec<Range<T>> = Vec::with_capacity(ranges.len());
+		for range in ranges {
+			match merged.last_mut() {
+				Some(last) if range.start <= last.end => {
+					last.end = last.end.max(range.end);
+				}
+				_ => merged.push(range),
+			}
+		}
+
+		Self { ranges: merged }
+	}
+
+	pub fn insert(&mut self, range: Range<T>) {
+		let mut ranges = std::mem::take(&mut self.ranges);
+		ranges.push(range);
+		*self = Self::normalized(ranges);
+	}
+
+	pub fn ranges(&self) -> &[Range<T>] {
+		&self.ranges
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.ranges.is_empty()
+	}
+
+	pub fn contains(&self, value: T) -> bool {
+		let index = self.ranges.partition_point(|range| range.end <= value);
+		self.ranges
+			.get(index)
+			.is_some_and(|range| range.start <= value)
+	}
+
+	/// Number of integers in the set.
+	pub fn len(&self) -> T {
+		self.ranges
+			.iter()
+			.fold(T::ZERO, |len, range| len + (range.end - range.start))
+	}
+
+	pub fn min(&self) -> Option<T> {
+		self.ranges.first().map(|range| range.start)
+	}
+
+	pub fn max(&self) -> Option<T> {
+		self.ranges.last().map(|range| range.end - T::ONE)
+	}
+
+	pub fn union(&self, other: &Self) -> Self {
+		Self::normalized(
+			self.ranges.iter().chain(&other.ranges).cloned().collect(),
+		)
+	}
+
+	pub fn intersection(&self, other: &Self) -> Self {
+		let mut ranges = Vec::new();
+		let (mut i, mut j) = (0, 0);
+
+		while let (Some(a), Some(b)) = (self.ranges.get(i), other.ranges.get(j))
+		{
+			let start = a.start.max(b.start);
+			let end = a.end.min(b.end);
+			if start < end {
+				ranges.push(start..end);
+			}
+
+			if a.end < b.end {
+				i += 1;
+			} else {
+				j += 1;
+			}
+		}
+
+		Self { ranges }
+	}
+
+	pub fn difference(&self, other: &Self) -> Self {
+		let mut ranges = Vec::new();
+		let mut j = 0;
+
+		for range in &self.ranges {
+			let mut start = range.start;
+
+			while let Some(cut) = other.ranges.get(j) {
+				if cut.end <= start {
+					j += 1;
+					continue;
+				}
+				if cut.start >= range.end {
+					break;
+				}
+				if cut.start > start {
+					ranges.push(start..cut.start);
+				}
+				start = start.max(cut.end);
+				if cut.end >= range.end {
+					break;
+				}
+				j += 1;
+			}
+
+			if start < range.end {
+				ranges.push(start..range.end);
+			}
+		}
+
+		Self { ranges }
+	}
+
+	/// The ranges of the set, further broken at every cut point that falls
+	/// strictly inside one of them.
+	pub fn split(&self, cuts: impl IntoIterator<Item = T>) -> Vec<Range<T>> {
+		let mut cuts: Vec<T> = cuts.into_iter().collect();
+		cuts.sort_unstable();
+		cuts.dedup();
+
+		let mut pieces = Vec::new();
+
+		for range in &self.ranges {
+			let first = cuts.partition_point(|&cut| cut <= range.start);
+			let mut start = range.start;
+
+			for &cut in cuts[first..].iter().take_while(|&&cut| cut < range.end)
+			{
+				pieces.push(start..cut);
+				start = cut;
+			}
+
+			pieces.push(start..range.end);
+		}
+
+		pieces
+	}
+
+	pub fn shift_up(&self, offset: T) -> Self {
+		Self {
+			ranges: self
+				.ranges
+				.iter()
+				.map(|range| range.start + offset..range.end + offset)
+				.collect(),
+		}
+	}
+
+	pub fn shift_down(&self, offset: T) -> Self {
+		Self {
+			ranges: self
+				.ranges
+				.iter()
+				.map(|range| range.start - offset..range.end - offset)
+				.collect(),
+		}
+	}
+}
+
+impl<T: Bound> From<Range<T>> for RangeSet<T> {
+	fn from(range: Range<T>) -> Self {
+		Self::normalized(vec![range])
+	}
+}
+
+impl<T: Bound> FromIterator<Range<T>> for RangeSet<T> {
+	fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+		Self::normalized(iter.into_iter().collect())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const DOMAIN: usize = 64;
+
+	type Bitmap = [bool; DOMAIN];
+
+	/// xorshift64, so the property tests are reproducible.
+	struct Rng(u64);
+
+	impl Rng {
+		fn next(&mut self, bound: u64) -> u64 {
+			self.0 ^= self.0 << 13;
+			self.0 ^= self.0 >> 7;
+			self.0 ^= self.0 << 17;
+			self.0 % bound
+		}
+
+		fn set(&mut self) -> RangeSet<u64> {
+			(0..self.next(5))
+				.map(|_| {
+					let start = self.next(DOMAIN as u64);
+					start..(start + self.next(12)).min(DOMAIN as u64)
+				})
+				.collect()
+		}
+	}
+
+	fn bitmap(set: &RangeSet<u64>) -> Bitmap {
+		let mut bits = [false; DOMAIN];
+		for (x, bit) in bits.iter_mut().enumerate() {
+			*bit = set.ranges().iter().any(|range| range.contains(&(x as u64)));
+		}
+		bits
+	}
+
+	fn combine(
+		a: &Bitmap,
+		b: &Bitmap,
+		f: impl Fn(bool, bool) -> bool,
+	) -> Bitmap {
+		std::array::from_fn(|x| f(a[x], b[x]))
+	}
+
+	fn is_normalized(set: &RangeSet<u64>) -> bool {
+		set.ranges().iter().all(|range| range.start < range.end)
+			&& set.ranges().windows(2).all(|w| w[0].end < w[1].start)
+	}
+
+	#[test]
+	fn matches_bitmap() {
+		let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+
+		for _ in 0..2000 {
+			let (a, b) = (rng.set(), rng.set());
+			let (bits_a, bits_b) = (bitmap(&a), bitmap(&b));
+
+			for (result, expected) in [
+				(a.union(&b), combine(&bits_a, &bits_b, |x, y| x || y)),
+				(a.intersection(&b), combine(&bits_a, &bits_b, |x, y| x && y)),
+				(a.difference(&b), combine(&bits_a, &bits_b, |x, y| x && !y)),
+			] {
+				assert!(is_normalized(&result), "{result:?}");
+				assert_eq!(bitmap(&result), expected, "{a:?} {b:?}");
+			}
+
+			let members: Vec<u64> =
+				(0..DOMAIN as u64).filter(|&x| bits_a[x as usize]).collect();
+			assert_eq!(a.len(), members.len() as u64);
+			assert_eq!(a.min(), members.first().copied());
+			assert_eq!(a.max(), members.last().copied());
+			assert!(
+				(0..DOMAIN as u64).all(|x| a.contains(x) == bits_a[x as usize])
+			);
+
+			let cuts: Vec<u64> =
+				(0..3).map(|_| rng.next(DOMAIN as u64)).collect();
+			let pieces = a.split(cuts.iter().copied());
+			assert_eq!(pieces.iter().cloned().collect::<RangeSet<_>>(), a);
+			assert!(pieces.iter().all(|piece| {
+				cuts.iter()
+					.all(|&cut| cut <= piece.start || cut >= piece.end)
+			}));
+
+			let shifted = a.shift_up(7);
+			assert_eq!(shifted.shift_down(7), a);
+			assert_eq!(shifted.len(), a.len());
+		}
+	}
+
+	#[test]
+	fn merges_adjacent() {
+		let set: RangeSet<i32> =
+			[5..8, -3..0, 0..2, 7..10].into_iter().collect();
+		assert_eq!(set.ranges(), &[-3..2, 5..10]);
+		assert_eq!(set.max(), Some(9));
+	}
+}
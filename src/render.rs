@@ -0,0 +1,330 @@
+use crate::grid::Grid;
+use anyhow::{anyhow, Result};
+use std::{fmt::Write, fs, path::Path};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+	pub const BLACK: Rgb = Rgb(0, 0, 0);
+	pub const WHITE: Rgb = Rgb(255, 255, 255);
+	pub const GREY: Rgb = Rgb(128, 128, 128);
+	pub const RED: Rgb = Rgb(220, 50, 47);
+	pub const GREEN: Rgb = Rgb(133, 153, 0);
+	pub const BLUE: Rgb = Rgb(38, 139, 210);
+	pub const YELLOW: Rgb = Rgb(181, 137, 0);
+
+	pub fn hex(self) -> String {
+		format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+	}
+}
+
+/// Rows of RGB bytes with every cell drawn as a `scale` x `scale` square.
+fn scanlines<T>(
+	grid: &Grid<T>,
+	scale: usize,
+	color: impl Fn(&T) -> Rgb,
+) -> impl Iterator<Item = Vec<u8>> + '_ {
+	let colors: Vec<Rgb> = grid.cells().iter().map(color).collect();
+	let width = grid.width();
+
+	(0..grid.height() * scale).map(move |row| {
+		let y = row / scale;
+		colors[y * width..(y + 1) * width]
+			.iter()
+			.flat_map(|&Rgb(r, g, b)| [r, g, b].repeat(scale))
+			.collect()
+	})
+}
+
+/// Binary (P6) PPM image of `grid`.
+pub fn ppm<T>(
+	grid: &Grid<T>,
+	scale: usize,
+	color: impl Fn(&T) -> Rgb,
+) -> Vec<u8> {
+	let mut bytes = format!(
+		"P6\n{} {}\n255\n",
+		grid.width() * scale,
+		grid.height() * scale
+	)
+	.into_bytes();
+
+	scanlines(grid, scale, color).for_each(|line| bytes.extend(line));
+
+	bytes
+}
+
+/// 8-bit RGB PNG image of `grid`. The pixel data is stored uncompressed
+/// inside the zlib stream, which every decoder accepts.
+pub fn png<T>(
+	grid: &Grid<T>,
+	scale: usize,
+	color: impl Fn(&T) -> Rgb,
+) -> Vec<u8> {
+	let (width, height) = (grid.width() * scale, grid.height() * scale);
+
+	let mut raw = Vec::with_capacity(height * (width * 3 + 1));
+	for line in scanlines(grid, scale, color) {
+		raw.push(0);
+		raw.extend(line);
+	}
+
+	let mut header = Vec::with_capacity(13);
+	header.extend((width as u32).to_be_bytes());
+	header.extend((height as u32).to_be_bytes());
+	header.extend([8, 2, 0, 0, 0]);
+
+	let mut bytes = b"\x89PNG\r\n\x1a\n".to_vec();
+	png_chunk(&mut bytes, b"IHDR", &header);
+	png_chunk(&mut bytes, b"IDAT", &zlib_stored(&raw));
+	png_chunk(&mut bytes, b"IEND", &[]);
+
+	bytes
+}
+
+fn png_chunk(bytes: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+	bytes.extend((data.len() as u32).to_be_bytes());
+	let start = bytes.len();
+	bytes.extend(kind);
+	bytes.extend(data);
+	let crc = crc32(&bytes[start..]);
+	bytes.extend(crc.to_be_bytes());
+}
+
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+	let mut bytes = vec![0x78, 0x01];
+	let mut blocks = data.chunks(u16::MAX as usize).peekable();
+
+	if blocks.peek().is_none() {
+		bytes.extend([1, 0, 0, 0xff, 0xff]);
+	}
+
+	while let Some(block) = blocks.next() {
+		let len = block.len() as u16;
+		bytes.push(blocks.peek().is_none() as u8);
+		bytes.extend(len.to_le_bytes());
+		bytes.extend((!len).to_le_bytes());
+		bytes.extend(block);
+	}
+
+	bytes.extend(adler32(data).to_be_bytes());
+	bytes
+}
+
+fn crc32(data: &[u8]) -> u32 {
+	!data.iter().fold(!0u32, |crc, &byte| {
+		(0..8).fold(crc ^ byte as u32, |crc, _| match crc & 1 {
+			1 => (crc >> 1) ^ 0xedb8_8320,
+			_ => crc >> 1,
+		})
+	})
+}
+
+fn adler32(data: &[u8]) -> u32 {
+	let (a, b) = data.iter().fold((1u32, 0u32), |(a, b), &byte| {
+		let a = (a + byte as u32) % 65521;
+		(a, (b + a) % 65521)
+	});
+	b << 16 | a
+}
+
+type Label<'a, T> = Box<dyn Fn(&T) -> Option<String> + 'a>;
+
+/// SVG image of a grid, with optional per-cell labels and polylines drawn
+/// through cell centers on top.
+pub struct Svg<'a, T> {
+	grid: &'a Grid<T>,
+	scale: usize,
+	color: Box<dyn Fn(&T) -> Rgb + 'a>,
+	label: Option<Label<'a, T>>,
+	paths: Vec<(Vec<(usize, usize)>, Rgb)>,
+}
+
+impl<'a, T> Svg<'a, T> {
+	pub fn new(
+		grid: &'a Grid<T>,
+		scale: usize,
+		color: impl Fn(&T) -> Rgb + 'a,
+	) -> Self {
+		Self {
+			grid,
+			scale,
+			color: Box::new(color),
+			label: None,
+			paths: Vec::new(),
+		}
+	}
+
+	pub fn with_labels(
+		mut self,
+		label: impl Fn(&T) -> Option<String> + 'a,
+	) -> Self {
+		self.label = Some(Box::new(label));
+		self
+	}
+
+	pub fn with_path(
+		mut self,
+		points: Vec<(usize, usize)>,
+		color: Rgb,
+	) -> Self {
+		self.paths.push((points, color));
+		self
+	}
+
+	pub fn render(&self) -> String {
+		let scale = self.scale;
+		let mut svg = String::new();
+
+		let _ = writeln!(
+			svg,
+			r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" shape-rendering="crispEdges">"#,
+			self.grid.width() * scale,
+			self.grid.height() * scale
+		);
+
+		for ((x, y), cell) in self.grid.iter() {
+			let _ = writeln!(
+				svg,
+				r#"<rect x="{}" y="{}" width="{scale}" height="{scale}" fill="{}"/>"#,
+				x * scale,
+				y * scale,
+				(self.color)(cell).hex()
+			);
+		}
+
+		if let Some(label) = &self.label {
+			for ((x, y), cell) in self.grid.iter() {
+				if let Some(text) = label(cell) {
+					let _ = writeln!(
+						svg,
+						r#"<text x="{}" y="{}" font-size="{}" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+						x * scale + scale / 2,
+						y * scale + scale / 2,
+						scale * 3 / 4,
+						escape(&text)
+					);
+				}
+			}
+		}
+
+		for (points, color) in &self.paths {
+			let points = points
+				.iter()
+				.map(|(x, y)| {
+					format!(
+						"{},{}",
+						x * scale + scale / 2,
+						y * scale + scale / 2
+					)
+				})
+				.collect::<Vec<_>>()
+				.join(" ");
+
+			let _ = writeln!(
+				svg,
+				r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="{}"/>"#,
+				points,
+				color.hex(),
+				(scale / 4).max(1)
+			);
+		}
+
+		svg.push_str("</svg>\n");
+		svg
+	}
+}
+
+fn escape(text: &str) -> String {
+	text.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+}
+
+/// Writes `grid` to `path`, picking PPM, PNG or SVG from the extension.
+pub fn save<T>(
+	path: impl AsRef<Path>,
+	grid: &Grid<T>,
+	scale: usize,
+	color: impl Fn(&T) -> Rgb,
+) -> Result<()> {
+	let path = path.as_ref();
+
+	let bytes = match path.extension().and_then(|e| e.to_str()) {
+		Some("ppm") => ppm(grid, scale, color),
+		Some("png") => png(grid, scale, color),
+		Some("svg") => Svg::new(grid, scale, color).render().into_bytes(),
+		_ => {
+			return Err(anyhow!("Unsupported image format: {}", path.display()))
+		}
+	};
+
+	fs::write(path, bytes)?;
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn checkerboard() -> Grid<bool> {
+		Grid::from_cells(2, 2, vec![true, false, false, true])
+	}
+
+	fn color(cell: &bool) -> Rgb {
+		match cell {
+			true => Rgb::WHITE,
+			false => Rgb::BLACK,
+		}
+	}
+
+	#[test]
+	fn checksums() {
+		assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+		assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+	}
+
+	#[test]
+	fn ppm_pixels() {
+		let bytes = ppm(&checkerboard(), 2, color);
+		let (header, pixels) = bytes.split_at(11);
+		assert_eq!(header, b"P6\n4 4\n255\n");
+		assert_eq!(pixels.len(), 4 * 4 * 3);
+		assert_eq!(
+			&pixels[..12],
+			&[255, 255, 255, 255, 255, 255, 0, 0, 0, 0, 0, 0]
+		);
+	}
+
+	#[test]
+	fn png_layout() {
+		let bytes = png(&checkerboard(), 3, color);
+		assert_eq!(&bytes[..8], b"\x89PNG\r\n\x1a\n");
+		assert_eq!(&bytes[12..16], b"IHDR");
+		assert_eq!(&bytes[16..24], &[0, 0, 0, 6, 0, 0, 0, 6]);
+		assert_eq!(&bytes[bytes.len() - 12..], b"\0\0\0\0IEND\xae\x42\x60\x82");
+
+		// Filter byte plus three bytes per pixel on each of the six rows,
+		// stored in a single block after the two byte zlib header.
+		let idat = &bytes[33..];
+		assert_eq!(&idat[..4], &[0, 0, 0, 2 + 5 + 6 * 19 + 4]);
+		assert_eq!(&idat[4..8], b"IDAT");
+		assert_eq!(&idat[8..15], &[0x78, 0x01, 1, 114, 0, 141, 255]);
+	}
+
+	#[test]
+	fn svg_labels_and_paths() {
+		let grid = checkerboard();
+		let svg = Svg::new(&grid, 10, color)
+			.with_labels(|cell| cell.then(|| "<#>".to_string()))
+			.with_path(vec![(0, 0), (1, 1)], Rgb::RED)
+			.render();
+
+		assert_eq!(svg.matches("<rect").count(), 4);
+		assert_eq!(svg.matches("&lt;#&gt;").count(), 2);
+		assert!(svg
+			.contains(r##"points="5,5 15,15" fill="none" stroke="#dc322f""##));
+	}
+}
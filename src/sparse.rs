@@ -0,0 +1,137 @@
+use std::collections::{hash_map, HashMap};
+
+pub type Point = (i64, i64);
+
+/// A grid that only stores the cells that are set, for coordinate spaces too
+/// large (or unbounded) for a dense `Vec`.
+#[derive(Debug, Clone)]
+pub struct SparseGrid<T> {
+	cells: HashMap<Point, T>,
+}
+
+impl<T> Default for SparseGrid<T> {
+	fn default() -> Self {
+		Self {
+			cells: HashMap::new(),
+		}
+	}
+}
+
+impl<T> SparseGrid<T> {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Parses a newline separated block of bytes, keeping the cells for which
+	/// `cell` returns a value. `x` is the column and `y` the row.
+	pub fn from_bytes(bytes: &[u8], cell: impl Fn(u8) -> Option<T>) -> Self {
+		let cell = &cell;
+		let cells = bytes
+			.split(|&b| b == b'\n')
+			.enumerate()
+			.flat_map(|(y, row)| {
+				row.iter().enumerate().filter_map(move |(x, &b)| {
+					cell(b).map(|value| ((x as i64, y as i64), value))
+				})
+			})
+			.collect();
+
+		Self { cells }
+	}
+
+	pub fn len(&self) -> usize {
+		self.cells.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.cells.is_empty()
+	}
+
+	pub fn get(&self, point: Point) -> Option<&T> {
+		self.cells.get(&point)
+	}
+
+	pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+		self.cells.get_mut(&point)
+	}
+
+	pub fn contains(&self, point: Point) -> bool {
+		self.cells.contains_key(&point)
+	}
+
+	pub fn insert(&mut self, point: Point, value: T) -> Option<T> {
+		self.cells.insert(point, value)
+	}
+
+	pub fn remove(&mut self, point: Point) -> Option<T> {
+		self.cells.remove(&point)
+	}
+
+	pub fn iter(&self) -> hash_map::Iter<'_, Point, T> {
+		self.cells.iter()
+	}
+
+	pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+		self.cells.keys().copied()
+	}
+
+	/// Inclusive `(min, max)` corners of the set cells.
+	pub fn bounds(&self) -> Option<(Point, Point)> {
+		self.cells
+			.keys()
+			.fold(None, |bounds, &(x, y)| match bounds {
+				None => Some(((x, y), (x, y))),
+				Some(((min_x, min_y), (max_x, max_y))) => Some((
+					(min_x.min(x), min_y.min(y)),
+					(max_x.max(x), max_y.max(y)),
+				)),
+			})
+	}
+}
+
+impl<T> FromIterator<(Point, T)> for SparseGrid<T> {
+	fn from_iter<I: IntoIterator<Item = (Point, T)>>(iter: I) -> Self {
+		Self {
+			cells: iter.into_iter().collect(),
+		}
+	}
+}
+
+impl<'a, T> IntoIterator for &'a SparseGrid<T> {
+	type Item = (&'a Point, &'a T);
+	type IntoIter = hash_map::Iter<'a, Point, T>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.cells.iter()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn from_bytes() {
+		let grid = SparseGrid::from_bytes(b"#..\n..#\n", |b| {
+			(b == b'#').then_some(())
+		});
+		assert_eq!(grid.len(), 2);
+		assert!(grid.contains((0, 0)));
+		assert!(grid.contains((2, 1)));
+		assert_eq!(grid.bounds(), Some(((0, 0), (2, 1))));
+	}
+
+	#[test]
+	fn huge_coordinates() {
+		let mut grid = SparseGrid::new();
+		grid.insert((-1_000_000_000, 7), 'a');
+		grid.insert((1_000_000_000, -7), 'b');
+		assert_eq!(grid.get((1_000_000_000, -7)), Some(&'b'));
+		assert_eq!(
+			grid.bounds(),
+			Some(((-1_000_000_000, -7), (1_000_000_000, 7)))
+		);
+		assert_eq!(grid.remove((-1_000_000_000, 7)), Some('a'));
+		assert_eq!(grid.len(), 1);
+	}
+}
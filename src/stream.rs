@@ -0,0 +1,214 @@
+use crate::{cli, diagnostic::Diagnostic, inputs, parse};
+use anyhow::{Context, Result};
+use std::{
+	fs::File,
+	io::{self, BufRead, BufReader, Cursor, Read},
+};
+
+/// The path that reads standard input instead of a file.
+pub const STDIN: &str = "-";
+
+/// Lines of a reader, read into a buffer that is reused from line to line.
+/// Memory use depends on the longest line, not on the size of the input.
+#[derive(Debug)]
+pub struct Lines<R> {
+	reader: R,
+	buffer: String,
+	number: usize,
+}
+
+impl<R: BufRead> Lines<R> {
+	pub fn new(reader: R) -> Self {
+		Self {
+			reader,
+			buffer: String::new(),
+			number: 0,
+		}
+	}
+
+	/// The next line without its line ending, numbered from 1, like
+	/// [`str::lines`]. The line is only borrowed until the next call.
+	pub fn next_line(&mut self) -> io::Result<Option<(usize, &str)>> {
+		self.buffer.clear();
+		if self.reader.read_line(&mut self.buffer)? == 0 {
+			return Ok(None);
+		}
+		self.number += 1;
+
+		let line = self.buffer.strip_suffix('\n').unwrap_or(&self.buffer);
+		let line = line.strip_suffix('\r').unwrap_or(line);
+		Ok(Some((self.number, line)))
+	}
+}
+
+/// A day's input, streamed a line at a time from a file or stdin, for
+/// inputs too big to read into a `String`.
+pub struct Input {
+	name: String,
+	lines: Lines<Box<dyn BufRead>>,
+}
+
+impl Input {
+	/// `name` is only used in error messages.
+	pub fn new(name: &str, reader: impl BufRead + 'static) -> Self {
+		Self {
+			name: name.to_string(),
+			lines: Lines::new(Box::new(reader)),
+		}
+	}
+
+	/// Opens `path` in the chosen profile, or stdin if it is `-`. Inputs
+	/// that are embedded or encrypted are read whole through
+	/// [`inputs::read`].
+	pub fn open(path: &str) -> Result<Self> {
+		if path == STDIN {
+			return Ok(Self::new(path, io::stdin().lock()));
+		}
+		let located = inputs::locate(path)?;
+		if inputs::embedded(&located).is_none() {
+			if let Ok(file) = File::open(&located) {
+				return Ok(Self::new(&located, BufReader::new(file)));
+			}
+		}
+
+		Ok(Self::new(&located, Cursor::new(inputs::read(path)?)))
+	}
+
+	/// Opens the path given as the first argument, or `default` without one.
+	pub fn from_args(default: &str) -> Result<Self> {
+		Self::open(&cli::args().next().unwrap_or_else(|| default.to_string()))
+	}
+
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// Calls `f` on every line. The first parse error stops the stream and
+	/// comes back as a diagnostic showing the offending line.
+	pub fn each_line(
+		mut self,
+		mut f: impl FnMut(&str) -> parse::Result<()>,
+	) -> Result<()> {
+		while let Some((number, line)) = self
+			.lines
+			.next_line()
+			.with_context(|| format!("Can't read `{}`", self.name))?
+		{
+			if let Err(error) = f(line) {
+				return Err(Diagnostic::from(error.on_line(number))
+					.in_file(self.name.as_str())
+					.with_line(line)
+					.into());
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// `line` repeated `count` times, without ever holding more than one copy
+/// of it. Makes inputs of any size for testing streamed solutions.
+#[derive(Debug, Clone)]
+pub struct Repeat {
+	line: Vec<u8>,
+	left: usize,
+	offset: usize,
+}
+
+impl Repeat {
+	pub fn new(line: impl Into<Vec<u8>>, count: usize) -> Self {
+		let line = line.into();
+		Self {
+			left: if line.is_empty() { 0 } else { count },
+			line,
+			offset: 0,
+		}
+	}
+}
+
+impl Read for Repeat {
+	fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+		let mut written = 0;
+		while written < buffer.len() && self.left > 0 {
+			let rest = &self.line[self.offset..];
+			let take = rest.len().min(buffer.len() - written);
+			buffer[written..written + take].copy_from_slice(&rest[..take]);
+			written += take;
+			self.offset += take;
+			if self.offset == self.line.len() {
+				self.offset = 0;
+				self.left -= 1;
+			}
+		}
+		Ok(written)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{alloc, parse::ParseError};
+
+	#[test]
+	fn lines_match_str_lines() {
+		let text = "a\r\nb\n\nc";
+		let mut lines = Lines::new(Cursor::new(text));
+		let mut streamed = Vec::new();
+		while let Some((number, line)) = lines.next_line().unwrap() {
+			streamed.push((number, line.to_string()));
+		}
+
+		let expected: Vec<_> = parse::lines(text)
+			.map(|(number, line)| (number, line.to_string()))
+			.collect();
+		assert_eq!(streamed, expected);
+	}
+
+	#[test]
+	fn errors_show_the_line() {
+		let input = Input::new("-", Cursor::new("1 2\n3 4\n5 x\n7 8\n"));
+		let mut seen = 0;
+		let error = input
+			.each_line(|line| {
+				seen += 1;
+				match line.split_once(' ') {
+					Some((_, "x")) => {
+						Err(ParseError::new(1, 3, "Invalid number"))
+					}
+					_ => Ok(()),
+				}
+			})
+			.unwrap_err();
+
+		let diagnostic = error.downcast::<Diagnostic>().unwrap();
+		assert_eq!(seen, 3);
+		assert_eq!(diagnostic.position, Some((3, 3)));
+		assert!(diagnostic.to_string().contains("3 | 5 x"));
+	}
+
+	#[test]
+	fn constant_memory() {
+		let lines = 1_000_000;
+		let reader = Repeat::new(b"two1nine 7pqrstsixteen\n", lines);
+
+		let (result, allocations) = alloc::measure(|| {
+			let mut count = 0;
+			let mut sum = 0;
+			Input::new("generated", BufReader::new(reader)).each_line(
+				|line| {
+					let digit =
+						line.bytes().find(u8::is_ascii_digit).ok_or_else(
+							|| ParseError::new(1, 1, "Line has no digits"),
+						)?;
+					count += 1;
+					sum += (digit - b'0') as usize;
+					Ok(())
+				},
+			)?;
+			anyhow::Ok((count, sum))
+		});
+
+		assert_eq!(result.unwrap(), (lines, lines));
+		assert!(allocations.bytes < 64 * 1024, "{allocations}");
+	}
+}